@@ -0,0 +1,221 @@
+use std::{
+	fs,
+	io::{self, Write},
+	path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Result};
+use clap::{ArgEnum, Subcommand};
+use ironworks::excel::{
+	export::{self, StringFormat},
+	Excel, Language, Sheet,
+};
+
+use crate::source::SourceArgs;
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// Dump the contents of a sheet.
+	Dump(DumpArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DumpArgs {
+	/// Name of the sheet to dump, i.e. `Item`.
+	sheet: String,
+
+	/// Language to read strings in. When `all`, each language supported by the
+	/// sheet is written to a separate file within the output directory.
+	#[clap(short, long, arg_enum, default_value = "en")]
+	lang: LanguageArg,
+
+	/// Output format.
+	#[clap(short, long, arg_enum, default_value = "csv")]
+	format: Format,
+
+	/// Representation of string fields.
+	#[clap(short, long, arg_enum, default_value = "resolved")]
+	strings: StringsArg,
+
+	/// File to write the dump to. Defaults to stdout. When dumping all
+	/// languages, this is the directory to write files to.
+	#[clap(short, long)]
+	output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+enum LanguageArg {
+	All,
+	None,
+	Ja,
+	En,
+	De,
+	Fr,
+	Chs,
+	Cht,
+	Kr,
+}
+
+impl LanguageArg {
+	fn language(self) -> Option<Language> {
+		let language = match self {
+			Self::All => return None,
+			Self::None => Language::None,
+			Self::Ja => Language::Japanese,
+			Self::En => Language::English,
+			Self::De => Language::German,
+			Self::Fr => Language::French,
+			Self::Chs => Language::ChineseSimplified,
+			Self::Cht => Language::ChineseTraditional,
+			Self::Kr => Language::Korean,
+		};
+		Some(language)
+	}
+}
+
+// File name suffix used when dumping all languages, i.e. `Item.en.csv`.
+fn language_suffix(language: Language) -> &'static str {
+	match language {
+		Language::None => "",
+		Language::Japanese => ".ja",
+		Language::English => ".en",
+		Language::German => ".de",
+		Language::French => ".fr",
+		Language::ChineseSimplified => ".chs",
+		Language::ChineseTraditional => ".cht",
+		Language::Korean => ".kr",
+	}
+}
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+enum Format {
+	Csv,
+	Json,
+}
+
+impl Format {
+	fn extension(self) -> &'static str {
+		match self {
+			Self::Csv => "csv",
+			Self::Json => "jsonl",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+enum StringsArg {
+	Resolved,
+	Text,
+	Tagged,
+}
+
+impl From<StringsArg> for StringFormat {
+	fn from(strings: StringsArg) -> Self {
+		match strings {
+			StringsArg::Resolved => Self::Resolved,
+			StringsArg::Text => Self::Text,
+			StringsArg::Tagged => Self::Tagged,
+		}
+	}
+}
+
+pub fn run(source: &SourceArgs, command: Command) -> Result<()> {
+	match command {
+		Command::Dump(args) => dump(source, args),
+	}
+}
+
+fn dump(source: &SourceArgs, args: DumpArgs) -> Result<()> {
+	let ironworks = source.open()?.into_ironworks();
+	let excel = Excel::new(&ironworks);
+	let sheet = excel.sheet(args.sheet.as_str())?;
+
+	if let Some(language) = args.lang.language() {
+		let writer: Box<dyn io::Write> = match &args.output {
+			Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+			None => Box::new(io::stdout().lock()),
+		};
+		return dump_language(&sheet, language, &args, writer);
+	}
+
+	let Some(directory) = &args.output else {
+		bail!("An output directory is required when dumping all languages.")
+	};
+
+	for language in sheet.languages()? {
+		let (path, file) = create_language_file(directory, &args, language)?;
+		dump_language(&sheet, language, &args, io::BufWriter::new(file))?;
+		eprintln!("{}", path.display());
+	}
+
+	Ok(())
+}
+
+// Create the file a language is dumped to. Sheet names may contain directory
+// segments, i.e. `quest/000/Foo`, which are created as required.
+fn create_language_file(
+	directory: &Path,
+	args: &DumpArgs,
+	language: Language,
+) -> Result<(PathBuf, fs::File)> {
+	let path = directory.join(format!(
+		"{}{}.{}",
+		args.sheet,
+		language_suffix(language),
+		args.format.extension()
+	));
+
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	let file = fs::File::create(&path)?;
+	Ok((path, file))
+}
+
+fn dump_language(
+	sheet: &Sheet<&str>,
+	language: Language,
+	args: &DumpArgs,
+	mut writer: impl Write,
+) -> Result<()> {
+	let mut options = export::with();
+	options.language(language).strings(args.strings.into());
+
+	match args.format {
+		Format::Csv => options.csv(sheet, &mut writer)?,
+		// Rows are written as JSON lines to allow streaming large sheets.
+		Format::Json => options.json_lines(sheet, &mut writer)?,
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use std::{env, fs, process};
+
+	use clap::Parser;
+	use ironworks::excel::Language;
+
+	use super::{create_language_file, DumpArgs};
+
+	#[derive(Parser)]
+	struct Args {
+		#[clap(flatten)]
+		dump: DumpArgs,
+	}
+
+	#[test]
+	fn nested_language_file() {
+		let directory = env::temp_dir().join(format!("ironworks-cli-exd-{}", process::id()));
+		let args = Args::try_parse_from(["dump", "quest/000/Foo", "--format", "json"]).unwrap();
+
+		let (path, _file) =
+			create_language_file(&directory, &args.dump, Language::English).unwrap();
+		assert_eq!(path, directory.join("quest/000/Foo.en.jsonl"));
+		assert!(path.is_file());
+
+		fs::remove_dir_all(&directory).unwrap();
+	}
+}
@@ -0,0 +1,40 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::source::SourceArgs;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+	/// Game paths of the files to extract.
+	#[clap(required = true)]
+	paths: Vec<String>,
+
+	/// Directory to extract files into. Files are written at their game path
+	/// within this directory. Pass `-` to write file contents to stdout.
+	#[clap(short, long, default_value = ".")]
+	output: PathBuf,
+}
+
+pub fn run(source: &SourceArgs, args: Args) -> Result<()> {
+	let data = source.open()?;
+
+	for path in &args.paths {
+		let bytes = data.read(path)?;
+
+		if args.output.as_os_str() == "-" {
+			std::io::stdout().write_all(&bytes)?;
+			continue;
+		}
+
+		let target = args.output.join(path.to_lowercase());
+		if let Some(parent) = target.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(&target, bytes)?;
+
+		eprintln!("{path} -> {}", target.display());
+	}
+
+	Ok(())
+}
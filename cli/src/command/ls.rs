@@ -0,0 +1,71 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::source::SourceArgs;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+	/// Category to list, optionally followed by a repository, i.e. `exd` or `bg/ex1`.
+	category: String,
+
+	/// File containing known game paths, one per line. Entries matching a known
+	/// path are listed by path rather than by hash.
+	#[clap(short, long)]
+	paths: Option<PathBuf>,
+
+	/// Include the location and size of each file.
+	#[clap(short, long)]
+	long: bool,
+}
+
+pub fn run(source: &SourceArgs, args: Args) -> Result<()> {
+	let data = source.open()?;
+
+	let known_paths = match &args.paths {
+		None => HashMap::new(),
+		Some(paths) => fs::read_to_string(paths)?
+			.lines()
+			.map(|line| line.trim().to_lowercase())
+			.filter_map(|path| {
+				let entry = data.entry(&path).ok()?;
+				Some(((entry.repository(), entry.category(), entry.hash()), path))
+			})
+			.collect::<HashMap<_, _>>(),
+	};
+
+	let mut entries = data.entries(&args.category)?;
+	entries.sort_by_key(|entry| entry.hash());
+
+	for entry in entries {
+		// Colliding entries know their own path - otherwise fall back to the
+		// provided path list, and finally the raw hash.
+		let name = entry
+			.path()
+			.clone()
+			.or_else(|| {
+				let key = (entry.repository(), entry.category(), entry.hash());
+				known_paths.get(&key).cloned()
+			})
+			.unwrap_or_else(|| format!("~{:016x}", entry.hash()));
+
+		if !args.long {
+			println!("{name}");
+			continue;
+		}
+
+		let location = entry.location();
+		let size = location
+			.size()
+			.map(|size| size.to_string())
+			.unwrap_or_else(|| "-".into());
+		println!(
+			"{:02x}\tdat{}\t{:08x}\t{size}\t{name}",
+			location.chunk(),
+			location.data_file(),
+			location.offset(),
+		);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod exd;
+pub mod extract;
+pub mod ls;
+pub mod patch;
+pub mod tex;
+pub mod verify;
+pub mod version;
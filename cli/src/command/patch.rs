@@ -0,0 +1,627 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs,
+	io::{self, BufReader, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use ironworks::{
+	file::{
+		patch::{
+			Chunk, FileOperation, FileOperationCommand, HeaderFileKind, HeaderKind, SqPackChunk,
+			SqPackFile, TargetPlatform, ZiPatch,
+		},
+		File,
+	},
+	sqpack::{BlockPayload, Platform},
+};
+
+use crate::{source::SourceArgs, utility::REPOSITORIES};
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// Print a summary of the chunks in a patch file.
+	View(ViewArgs),
+
+	/// Apply one or more patch files to a game installation, in order.
+	Apply(ApplyArgs),
+
+	/// List the files that changed in a category between two patch versions.
+	/// Requires --patches.
+	Diff(DiffArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ViewArgs {
+	/// Path to the patch file.
+	patch: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ApplyArgs {
+	/// Paths to the patch files to apply.
+	#[clap(required = true)]
+	patches: Vec<PathBuf>,
+
+	/// Game directory to apply the patches to, i.e. the directory containing `sqpack`.
+	#[clap(short, long)]
+	target: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DiffArgs {
+	/// Category to diff, optionally followed by a repository, i.e. `exd` or `bg/ex1`.
+	category: String,
+
+	/// Patch version to diff from, i.e. `2023.01.01.0000.0000`.
+	#[clap(long)]
+	from: String,
+
+	/// Patch version to diff to. Defaults to the latest available patch.
+	#[clap(long)]
+	to: Option<String>,
+}
+
+pub fn run(source: &SourceArgs, command: Command) -> Result<()> {
+	match command {
+		Command::View(args) => view(args),
+		Command::Apply(args) => apply(args),
+		Command::Diff(args) => diff(source, args),
+	}
+}
+
+fn read_patch(path: &Path) -> Result<ZiPatch> {
+	let file = BufReader::new(fs::File::open(path)?);
+	ZiPatch::read(file).with_context(|| format!("Failed to read patch {}.", path.display()))
+}
+
+fn view(args: ViewArgs) -> Result<()> {
+	let patch = read_patch(&args.patch)?;
+	let mut target = SqPackTarget::default();
+
+	for chunk in patch.chunks() {
+		match chunk? {
+			Chunk::FileHeader(header) => println!(
+				"FHDR\tversion {}\t{:?}\t{} entry files",
+				header.version(),
+				header.patch_kind(),
+				header.entry_files()
+			),
+			Chunk::Apply(apply) => println!("APLY\t{:?}\t{}", apply.option(), apply.value()),
+			Chunk::AddDirectory(directory) => println!("ADIR\t{}", directory.path()),
+			Chunk::DeleteDirectory(directory) => println!("DELD\t{}", directory.path()),
+			Chunk::EndOfFile => println!("EOF_"),
+
+			Chunk::SqPack(command) => match command {
+				SqPackChunk::Add(add) => println!(
+					"SQPK A\t{}\toffset {:#x}\tdata {:#x}\tdelete {:#x}",
+					target.dat_path(add.file()),
+					add.target_offset(),
+					add.data_size(),
+					add.delete_size()
+				),
+				SqPackChunk::Delete(delete) => println!(
+					"SQPK D\t{}\toffset {:#x}\tdelete {:#x}",
+					target.dat_path(delete.file()),
+					delete.target_offset(),
+					delete.delete_size()
+				),
+				SqPackChunk::Expand(expand) => println!(
+					"SQPK E\t{}\toffset {:#x}\tdelete {:#x}",
+					target.dat_path(expand.file()),
+					expand.target_offset(),
+					expand.delete_size()
+				),
+				SqPackChunk::FileOperation(operation) => {
+					let kind = match operation.operation() {
+						FileOperation::AddFile(blocks) => format!("add ({} blocks)", blocks.len()),
+						FileOperation::DeleteFile => "delete".into(),
+						FileOperation::MakeDirTree => "mkdir".into(),
+						FileOperation::RemoveAll => "remove all".into(),
+					};
+					println!(
+						"SQPK F\t{}\t{kind}\toffset {:#x}\tsize {:#x}",
+						operation.path(),
+						operation.target_offset(),
+						operation.target_size()
+					)
+				}
+				SqPackChunk::HeaderUpdate(update) => println!(
+					"SQPK H\t{}\t{:?}",
+					target.header_path(update.file_kind(), update.file()),
+					update.header_kind()
+				),
+				SqPackChunk::IndexUpdate(update) => println!(
+					"SQPK I\t{:?}\t{:016x}\tsynonym {}",
+					update.kind(),
+					update.file_hash(),
+					update.is_synonym()
+				),
+				SqPackChunk::PatchInfo(info) => println!(
+					"SQPK X\tversion {}\tinstall size {}",
+					info.version(),
+					info.install_size()
+				),
+				SqPackChunk::TargetInfo(info) => {
+					target.set_platform(info.platform())?;
+					println!(
+						"SQPK T\t{:?}\t{:?}\tdebug {}\tversion {}",
+						info.platform(),
+						info.region(),
+						info.is_debug(),
+						info.version()
+					)
+				}
+			},
+		}
+	}
+
+	Ok(())
+}
+
+fn apply(args: ApplyArgs) -> Result<()> {
+	for path in &args.patches {
+		eprintln!("Applying {}", path.display());
+		apply_patch(path, &args.target)
+			.with_context(|| format!("Failed to apply patch {}.", path.display()))?;
+	}
+
+	Ok(())
+}
+
+fn apply_patch(path: &Path, game: &Path) -> Result<()> {
+	let patch = read_patch(path)?;
+	let mut source = BufReader::new(fs::File::open(path)?);
+	let mut files = FileCache::default();
+	let mut target = SqPackTarget::default();
+
+	for chunk in patch.chunks() {
+		match chunk? {
+			Chunk::AddDirectory(directory) => fs::create_dir_all(game.join(directory.path()))?,
+			Chunk::DeleteDirectory(directory) => {
+				ignore_missing(fs::remove_dir(game.join(directory.path())))?
+			}
+
+			Chunk::SqPack(command) => match command {
+				SqPackChunk::Add(add) => {
+					let file = files.get(&game.join(target.dat_path(add.file())))?;
+
+					source.seek(SeekFrom::Start(add.source_offset()))?;
+					file.seek(SeekFrom::Start(add.target_offset().into()))?;
+					io::copy(&mut (&mut source).take(add.data_size().into()), file)?;
+					write_zeros(file, add.delete_size().into())?;
+				}
+
+				SqPackChunk::Delete(delete) => {
+					let file = files.get(&game.join(target.dat_path(delete.file())))?;
+					write_empty_block(file, delete.target_offset(), delete.delete_size())?;
+				}
+
+				SqPackChunk::Expand(expand) => {
+					let file = files.get(&game.join(target.dat_path(expand.file())))?;
+					write_empty_block(file, expand.target_offset(), expand.delete_size())?;
+				}
+
+				SqPackChunk::HeaderUpdate(update) => {
+					let path = target.header_path(update.file_kind(), update.file());
+					let file = files.get(&game.join(path))?;
+
+					let offset = match update.header_kind() {
+						HeaderKind::Version => 0,
+						HeaderKind::Data | HeaderKind::Index => 1024,
+					};
+
+					source.seek(SeekFrom::Start(update.offset()))?;
+					file.seek(SeekFrom::Start(offset))?;
+					io::copy(&mut (&mut source).take(update.size().into()), file)?;
+				}
+
+				SqPackChunk::FileOperation(operation) => {
+					apply_file_operation(&operation, game, &mut source, &mut files)?
+				}
+
+				SqPackChunk::TargetInfo(info) => target.set_platform(info.platform())?,
+
+				// Index updates are unused in practice, and patch info is informational.
+				SqPackChunk::IndexUpdate(_) | SqPackChunk::PatchInfo(_) => {}
+			},
+
+			Chunk::FileHeader(_) | Chunk::Apply(_) | Chunk::EndOfFile => {}
+		}
+	}
+
+	files.flush()
+}
+
+fn apply_file_operation(
+	operation: &FileOperationCommand,
+	game: &Path,
+	source: &mut BufReader<fs::File>,
+	files: &mut FileCache,
+) -> Result<()> {
+	let path = game.join(operation.path().to_string());
+
+	match operation.operation() {
+		FileOperation::AddFile(blocks) => {
+			// An offset of 0 marks the start of a new file - any existing content is discarded.
+			if operation.target_offset() == 0 {
+				files.remove(&path);
+				if let Some(parent) = path.parent() {
+					fs::create_dir_all(parent)?;
+				}
+				fs::File::create(&path)?;
+			}
+
+			let file = files.get(&path)?;
+			file.seek(SeekFrom::Start(operation.target_offset()))?;
+			for block in blocks {
+				source.seek(SeekFrom::Start(block.offset()))?;
+				let mut payload =
+					BlockPayload::new(source, block.compressed_size(), block.decompressed_size());
+				io::copy(&mut payload, file)?;
+			}
+		}
+
+		FileOperation::DeleteFile => {
+			files.remove(&path);
+			ignore_missing(fs::remove_file(&path))?;
+		}
+
+		FileOperation::MakeDirTree => fs::create_dir_all(&path)?,
+
+		FileOperation::RemoveAll => {
+			let repository = repository_name(operation.repository_id())?;
+			for directory in ["sqpack", "movie"] {
+				let directory = game.join(directory).join(repository);
+				let entries = match fs::read_dir(&directory) {
+					Ok(entries) => entries,
+					Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+					Err(error) => return Err(error.into()),
+				};
+
+				for entry in entries {
+					let path = entry?.path();
+					if path.is_file() {
+						files.remove(&path);
+						fs::remove_file(&path)?;
+					}
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn diff(source: &SourceArgs, args: DiffArgs) -> Result<()> {
+	let from = source.open_at(&args.from)?;
+	let to = match &args.to {
+		Some(version) => source.open_at(version)?,
+		None => source.open()?,
+	};
+
+	// Files are keyed by hash, and by path where the hash alone is ambiguous.
+	let entries = |data: &crate::source::GameData| -> Result<BTreeMap<_, _>> {
+		let entries = match data.entries(&args.category) {
+			Ok(entries) => entries,
+			Err(ironworks::Error::NotFound(_)) => vec![],
+			Err(error) => return Err(error.into()),
+		};
+
+		Ok(entries
+			.into_iter()
+			.map(|entry| {
+				let location = entry.location();
+				let value = (location.chunk(), location.data_file(), location.offset());
+				((entry.hash(), entry.path().clone()), value)
+			})
+			.collect())
+	};
+
+	for line in diff_entries(&entries(&from)?, &entries(&to)?) {
+		println!("{line}");
+	}
+
+	Ok(())
+}
+
+type EntryKey = (u64, Option<String>);
+
+// Build the lines describing the entries added, modified, and removed between
+// two sets of index entries, keyed by hash and path.
+fn diff_entries<V: PartialEq>(
+	from: &BTreeMap<EntryKey, V>,
+	to: &BTreeMap<EntryKey, V>,
+) -> Vec<String> {
+	let name = |(hash, path): &EntryKey| path.clone().unwrap_or_else(|| format!("~{hash:016x}"));
+
+	let mut lines = Vec::new();
+
+	for (key, location) in to {
+		match from.get(key) {
+			None => lines.push(format!("+\t{}", name(key))),
+			Some(previous) if previous != location => lines.push(format!("M\t{}", name(key))),
+			Some(_) => {}
+		}
+	}
+
+	for key in from.keys() {
+		if !to.contains_key(key) {
+			lines.push(format!("-\t{}", name(key)));
+		}
+	}
+
+	lines
+}
+
+/// Tracks the platform targeted by a patch, and builds paths to the SqPack
+/// files it modifies.
+#[derive(Debug, Default)]
+struct SqPackTarget {
+	platform: Platform,
+}
+
+impl SqPackTarget {
+	fn set_platform(&mut self, platform: TargetPlatform) -> Result<()> {
+		self.platform = match platform {
+			TargetPlatform::Win32 => Platform::Win32,
+			TargetPlatform::Ps3 => Platform::PS3,
+			TargetPlatform::Ps4 => Platform::PS4,
+			TargetPlatform::Unknown => bail!("Unknown patch target platform."),
+		};
+		Ok(())
+	}
+
+	fn dat_path(&self, file: SqPackFile) -> String {
+		self.path(file, &format!("dat{}", file.file_id()))
+	}
+
+	fn index_path(&self, file: SqPackFile) -> String {
+		match file.file_id() {
+			0 => self.path(file, "index"),
+			id => self.path(file, &format!("index{id}")),
+		}
+	}
+
+	fn header_path(&self, kind: HeaderFileKind, file: SqPackFile) -> String {
+		match kind {
+			HeaderFileKind::Dat => self.dat_path(file),
+			HeaderFileKind::Index => self.index_path(file),
+		}
+	}
+
+	fn path(&self, file: SqPackFile, extension: &str) -> String {
+		// The high byte of the sub ID is the repository. Unknown repositories are
+		// formatted directly to keep the path useful for display.
+		let repository_id = file.sub_id() >> 8;
+		let repository = match REPOSITORIES.get(usize::from(repository_id)) {
+			Some(repository) => repository.to_string(),
+			None => format!("ex{repository_id}"),
+		};
+
+		format!(
+			"sqpack/{repository}/{:02x}{:04x}.{}.{extension}",
+			file.main_id(),
+			file.sub_id(),
+			self.platform.suffix()
+		)
+	}
+}
+
+fn repository_name(id: u16) -> Result<&'static str> {
+	REPOSITORIES
+		.get(usize::from(id))
+		.copied()
+		.with_context(|| format!("Unknown repository {id}."))
+}
+
+/// Open file handles for the files being patched.
+#[derive(Debug, Default)]
+struct FileCache {
+	files: HashMap<PathBuf, fs::File>,
+}
+
+impl FileCache {
+	fn get(&mut self, path: &Path) -> Result<&mut fs::File> {
+		if !self.files.contains_key(path) {
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			let file = fs::OpenOptions::new()
+				.read(true)
+				.write(true)
+				.create(true)
+				.truncate(false)
+				.open(path)?;
+			self.files.insert(path.to_path_buf(), file);
+		}
+
+		Ok(self.files.get_mut(path).unwrap())
+	}
+
+	fn remove(&mut self, path: &Path) {
+		self.files.remove(path);
+	}
+
+	fn flush(&mut self) -> Result<()> {
+		for file in self.files.values_mut() {
+			file.flush()?;
+		}
+		Ok(())
+	}
+}
+
+fn write_zeros(writer: &mut impl Write, size: u64) -> io::Result<()> {
+	io::copy(&mut io::repeat(0).take(size), writer)?;
+	Ok(())
+}
+
+// Deleted regions of dat files are zeroed, and marked with an empty block
+// header spanning the region.
+fn write_empty_block(file: &mut fs::File, offset: u32, size: u32) -> Result<()> {
+	const BLOCK_SHIFT: u32 = 7;
+
+	file.seek(SeekFrom::Start(offset.into()))?;
+	write_zeros(file, size.into())?;
+
+	let block_count = size >> BLOCK_SHIFT;
+	file.seek(SeekFrom::Start(offset.into()))?;
+	for value in [1 << BLOCK_SHIFT, 0, 0, block_count.saturating_sub(1), 0] {
+		file.write_all(&u32::to_le_bytes(value))?;
+	}
+
+	Ok(())
+}
+
+fn ignore_missing(result: io::Result<()>) -> io::Result<()> {
+	match result {
+		Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+		other => other,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{collections::BTreeMap, env, fs, path::PathBuf, process};
+
+	use super::{apply_patch, diff_entries};
+
+	const BLOCK_SIZE: usize = 128;
+
+	// Build a patch chunk with the provided magic and payload.
+	fn chunk(magic: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+		let mut bytes = u32::try_from(payload.len()).unwrap().to_be_bytes().to_vec();
+		bytes.extend(magic);
+		bytes.extend(payload);
+		bytes.extend([0u8; 4]);
+		bytes
+	}
+
+	// Build a SQPK chunk with the provided command magic and data.
+	fn sqpack_chunk(command: u8, data: &[u8]) -> Vec<u8> {
+		let size = u32::try_from(data.len() + 5).unwrap();
+		let mut payload = size.to_be_bytes().to_vec();
+		payload.push(command);
+		payload.extend(data);
+		chunk(b"SQPK", &payload)
+	}
+
+	// Target `sqpack/ffxiv/0a0000.win32.dat0`, in blocks of 128 bytes.
+	fn dat_command(blocks: &[u32]) -> Vec<u8> {
+		let mut data = vec![0u8; 3];
+		data.extend(0x0Au16.to_be_bytes());
+		data.extend(0u16.to_be_bytes());
+		data.extend(0u32.to_be_bytes());
+		for block in blocks {
+			data.extend(block.to_be_bytes());
+		}
+		data
+	}
+
+	fn add_file_command(path: &str, content: &[u8]) -> Vec<u8> {
+		let mut path = path.as_bytes().to_vec();
+		path.push(0);
+
+		let mut data = vec![b'A', 0, 0];
+		data.extend(0u64.to_be_bytes());
+		data.extend(u64::try_from(content.len()).unwrap().to_be_bytes());
+		data.extend(u32::try_from(path.len()).unwrap().to_be_bytes());
+		data.extend(0u16.to_be_bytes());
+		data.extend([0u8; 2]);
+		data.extend(path);
+
+		// Single uncompressed block, padded to the block alignment.
+		let block_start = data.len();
+		data.extend(16u32.to_le_bytes());
+		data.extend([0u8; 4]);
+		data.extend(32_000u32.to_le_bytes());
+		data.extend(u32::try_from(content.len()).unwrap().to_le_bytes());
+		data.extend(content);
+		data.resize(block_start + ((content.len() + 0x8F) & !0x7F), 0);
+
+		data
+	}
+
+	fn build_patch() -> Vec<u8> {
+		let mut bytes = b"\x91ZIPATCH\x0D\x0A\x1A\x0A".to_vec();
+
+		let mut directory = 12u32.to_be_bytes().to_vec();
+		directory.extend(b"sqpack/ffxiv");
+		bytes.extend(chunk(b"ADIR", &directory));
+
+		// Write two blocks of data, then delete the second.
+		let mut add = dat_command(&[0, 2, 0]);
+		add.extend([0xAAu8; BLOCK_SIZE * 2]);
+		bytes.extend(sqpack_chunk(b'A', &add));
+		bytes.extend(sqpack_chunk(b'D', &dat_command(&[1, 1])));
+
+		bytes.extend(sqpack_chunk(
+			b'F',
+			&add_file_command("game/file.txt", b"hello"),
+		));
+
+		bytes.extend(chunk(b"EOF_", &[]));
+		bytes
+	}
+
+	fn temp_directory(name: &str) -> PathBuf {
+		let directory = env::temp_dir().join(format!("ironworks-cli-{name}-{}", process::id()));
+		fs::create_dir_all(&directory).unwrap();
+		directory
+	}
+
+	#[test]
+	fn apply() {
+		let directory = temp_directory("patch-apply");
+		let patch = directory.join("D2023.01.01.0000.0000.patch");
+		fs::write(&patch, build_patch()).unwrap();
+
+		let game = directory.join("game");
+		apply_patch(&patch, &game).unwrap();
+
+		let dat = fs::read(game.join("sqpack/ffxiv/0a0000.win32.dat0")).unwrap();
+		assert_eq!(dat.len(), BLOCK_SIZE * 2);
+		assert_eq!(dat[..BLOCK_SIZE], [0xAA; BLOCK_SIZE]);
+		// Deleted blocks are zeroed, with an empty block header.
+		assert_eq!(dat[BLOCK_SIZE..BLOCK_SIZE + 4], 128u32.to_le_bytes());
+		assert!(dat[BLOCK_SIZE + 4..].iter().all(|&byte| byte == 0));
+
+		assert_eq!(fs::read(game.join("game/file.txt")).unwrap(), b"hello");
+
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn apply_invalid() {
+		let directory = temp_directory("patch-invalid");
+		let patch = directory.join("invalid.patch");
+		let mut bytes = build_patch();
+		bytes.truncate(bytes.len() - 20);
+		fs::write(&patch, bytes).unwrap();
+
+		apply_patch(&patch, &directory.join("game")).unwrap_err();
+
+		fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn diff() {
+		let from = BTreeMap::from([
+			((1, None), 0x100),
+			((2, None), 0x200),
+			((3, Some("exd/collision.exh".to_string())), 0x300),
+		]);
+		let to = BTreeMap::from([((1, None), 0x100), ((2, None), 0x280), ((4, None), 0x400)]);
+
+		assert_eq!(
+			diff_entries(&from, &to),
+			[
+				"M\t~0000000000000002",
+				"+\t~0000000000000004",
+				"-\texd/collision.exh",
+			]
+		);
+	}
+}
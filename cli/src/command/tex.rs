@@ -0,0 +1,227 @@
+use std::{fs, io::Cursor, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use image::RgbaImage;
+use ironworks::file::{
+	tex::{Format, Texture},
+	File,
+};
+
+use crate::source::SourceArgs;
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// Convert a texture to an image. The output format is inferred from the
+	/// output file extension.
+	Convert(ConvertArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConvertArgs {
+	/// Game path of the texture to convert.
+	input: String,
+
+	/// Path to write the converted image to, i.e. `icon.png`.
+	output: PathBuf,
+
+	/// Read the input texture from the local filesystem rather than game data.
+	#[clap(long)]
+	local: bool,
+}
+
+pub fn run(source: &SourceArgs, command: Command) -> Result<()> {
+	match command {
+		Command::Convert(args) => convert(source, args),
+	}
+}
+
+fn convert(source: &SourceArgs, args: ConvertArgs) -> Result<()> {
+	let bytes = match args.local {
+		true => fs::read(&args.input)?,
+		false => source.open()?.read(&args.input)?,
+	};
+
+	let texture = Texture::read(Cursor::new(bytes))?;
+	let image = decode(&texture)?;
+	image.save(&args.output)?;
+
+	Ok(())
+}
+
+fn decode(texture: &Texture) -> Result<RgbaImage> {
+	let width = u32::from(texture.width());
+	let height = u32::from(texture.height());
+	let data = texture.data();
+
+	// Only the first mip level of the first surface is decoded.
+	let pixels = usize::from(texture.width()) * usize::from(texture.height());
+	let rgba = match texture.format() {
+		Format::L8 => map_pixels(data, 1, pixels, |p| [p[0], p[0], p[0], 0xFF])?,
+		Format::A8 => map_pixels(data, 1, pixels, |p| [0xFF, 0xFF, 0xFF, p[0]])?,
+
+		Format::Rgba4 => map_pixels(data, 2, pixels, |p| {
+			let value = u16::from_le_bytes([p[0], p[1]]);
+			let channel = |shift: u16| (((value >> shift) & 0xF) as u8) * 0x11;
+			[channel(8), channel(4), channel(0), channel(12)]
+		})?,
+
+		Format::Rgb5a1 => map_pixels(data, 2, pixels, |p| {
+			let value = u16::from_le_bytes([p[0], p[1]]);
+			let channel = |shift: u16| {
+				let bits = ((value >> shift) & 0x1F) as u8;
+				(bits << 3) | (bits >> 2)
+			};
+			let alpha = if value & 0x8000 != 0 { 0xFF } else { 0 };
+			[channel(10), channel(5), channel(0), alpha]
+		})?,
+
+		Format::Argb8 | Format::Argb82 => {
+			map_pixels(data, 4, pixels, |p| [p[2], p[1], p[0], p[3]])?
+		}
+		Format::Rgbx8 => map_pixels(data, 4, pixels, |p| [p[2], p[1], p[0], 0xFF])?,
+
+		Format::Dxt1 => decode_dxt(data, width, height, 8, decode_bc1_block)?,
+		Format::Dxt3 => decode_dxt(data, width, height, 16, decode_bc2_block)?,
+		Format::Dxt5 => decode_dxt(data, width, height, 16, decode_bc3_block)?,
+
+		other => bail!("Unsupported texture format {other:?}."),
+	};
+
+	RgbaImage::from_raw(width, height, rgba).context("Decoded texture has invalid dimensions.")
+}
+
+fn map_pixels(
+	data: &[u8],
+	bytes_per_pixel: usize,
+	pixels: usize,
+	map: impl Fn(&[u8]) -> [u8; 4],
+) -> Result<Vec<u8>> {
+	let size = bytes_per_pixel * pixels;
+	let data = data
+		.get(..size)
+		.context("Texture data is smaller than its dimensions.")?;
+
+	Ok(data.chunks_exact(bytes_per_pixel).flat_map(map).collect())
+}
+
+type BlockDecoder = fn(&[u8]) -> [[u8; 4]; 16];
+
+fn decode_dxt(
+	data: &[u8],
+	width: u32,
+	height: u32,
+	block_size: usize,
+	decode_block: BlockDecoder,
+) -> Result<Vec<u8>> {
+	let width = usize::try_from(width)?;
+	let height = usize::try_from(height)?;
+	let blocks_wide = width.div_ceil(4);
+	let blocks_high = height.div_ceil(4);
+
+	let data = data
+		.get(..blocks_wide * blocks_high * block_size)
+		.context("Texture data is smaller than its dimensions.")?;
+
+	let mut output = vec![0u8; width * height * 4];
+	for (index, block) in data.chunks_exact(block_size).enumerate() {
+		let block_x = (index % blocks_wide) * 4;
+		let block_y = (index / blocks_wide) * 4;
+
+		for (pixel_index, pixel) in decode_block(block).iter().enumerate() {
+			let x = block_x + pixel_index % 4;
+			let y = block_y + pixel_index / 4;
+			if x >= width || y >= height {
+				continue;
+			}
+
+			let offset = (y * width + x) * 4;
+			output[offset..offset + 4].copy_from_slice(pixel);
+		}
+	}
+
+	Ok(output)
+}
+
+fn decode_bc1_block(block: &[u8]) -> [[u8; 4]; 16] {
+	decode_colour_block(block, true)
+}
+
+fn decode_bc2_block(block: &[u8]) -> [[u8; 4]; 16] {
+	let mut pixels = decode_colour_block(&block[8..], false);
+
+	// Explicit 4-bit alpha, two pixels per byte.
+	for (index, pixel) in pixels.iter_mut().enumerate() {
+		let nibble = (block[index / 2] >> ((index % 2) * 4)) & 0xF;
+		pixel[3] = nibble * 0x11;
+	}
+
+	pixels
+}
+
+fn decode_bc3_block(block: &[u8]) -> [[u8; 4]; 16] {
+	let mut pixels = decode_colour_block(&block[8..], false);
+
+	// Interpolated alpha, with 3-bit indices into an 8 entry palette.
+	let (alpha0, alpha1) = (u16::from(block[0]), u16::from(block[1]));
+	let palette: [u8; 8] = std::array::from_fn(|index| {
+		let index = index as u16;
+		let value = match (index, alpha0 > alpha1) {
+			(0, _) => alpha0,
+			(1, _) => alpha1,
+			(_, true) => ((8 - index) * alpha0 + (index - 1) * alpha1) / 7,
+			(6, false) => 0,
+			(7, false) => 255,
+			(_, false) => ((6 - index) * alpha0 + (index - 1) * alpha1) / 5,
+		};
+		value as u8
+	});
+
+	let indices = block[2..8]
+		.iter()
+		.rev()
+		.fold(0u64, |acc, &byte| acc << 8 | u64::from(byte));
+	for (index, pixel) in pixels.iter_mut().enumerate() {
+		pixel[3] = palette[((indices >> (index * 3)) & 0x7) as usize];
+	}
+
+	pixels
+}
+
+fn decode_colour_block(block: &[u8], allow_transparent: bool) -> [[u8; 4]; 16] {
+	let colour0 = u16::from_le_bytes([block[0], block[1]]);
+	let colour1 = u16::from_le_bytes([block[2], block[3]]);
+	let (c0, c1) = (rgb565(colour0), rgb565(colour1));
+
+	let mix = |a: u8, b: u8, a_weight: u16, b_weight: u16| {
+		((u16::from(a) * a_weight + u16::from(b) * b_weight) / (a_weight + b_weight)) as u8
+	};
+	let blend = |a_weight: u16, b_weight: u16| {
+		[
+			mix(c0[0], c1[0], a_weight, b_weight),
+			mix(c0[1], c1[1], a_weight, b_weight),
+			mix(c0[2], c1[2], a_weight, b_weight),
+			0xFF,
+		]
+	};
+
+	let palette = match colour0 > colour1 || !allow_transparent {
+		true => [c0, c1, blend(2, 1), blend(1, 2)],
+		false => [c0, c1, blend(1, 1), [0, 0, 0, 0]],
+	};
+
+	let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+	std::array::from_fn(|index| palette[((indices >> (index * 2)) & 0x3) as usize])
+}
+
+fn rgb565(value: u16) -> [u8; 4] {
+	let red = ((value >> 11) & 0x1F) as u8;
+	let green = ((value >> 5) & 0x3F) as u8;
+	let blue = (value & 0x1F) as u8;
+	[
+		(red << 3) | (red >> 2),
+		(green << 2) | (green >> 4),
+		(blue << 3) | (blue >> 2),
+		0xFF,
+	]
+}
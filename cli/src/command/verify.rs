@@ -0,0 +1,68 @@
+use anyhow::{bail, Result};
+
+use crate::{
+	source::SourceArgs,
+	utility::{CATEGORIES, REPOSITORIES},
+};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+	/// Only verify the specified category, optionally followed by a repository,
+	/// i.e. `exd` or `bg/ex1`. All categories are verified if omitted.
+	category: Option<String>,
+}
+
+pub fn run(source: &SourceArgs, args: Args) -> Result<()> {
+	let data = source.open()?;
+
+	let targets = match args.category {
+		Some(category) => vec![category],
+		None => CATEGORIES
+			.iter()
+			.flat_map(|category| {
+				REPOSITORIES
+					.iter()
+					.map(move |repository| format!("{category}/{repository}"))
+			})
+			.collect(),
+	};
+
+	let mut checked = 0usize;
+	let mut failures = 0usize;
+
+	for target in targets {
+		let entries = match data.entries(&target) {
+			Ok(entries) => entries,
+			// Not every category exists in every repository.
+			Err(ironworks::Error::NotFound(_)) => continue,
+			Err(error) => {
+				failures += 1;
+				eprintln!("{target}: failed to read index: {error}");
+				continue;
+			}
+		};
+
+		let count = entries.len();
+		for entry in entries {
+			checked += 1;
+			if let Err(error) = data.read_entry(&entry) {
+				failures += 1;
+				let name = entry
+					.path()
+					.clone()
+					.unwrap_or_else(|| format!("~{:016x}", entry.hash()));
+				eprintln!("{target}: {name}: {error:#}");
+			}
+		}
+
+		eprintln!("{target}: checked {count} files");
+	}
+
+	if failures > 0 {
+		bail!("{failures} of {checked} files failed verification.");
+	}
+
+	println!("All {checked} files verified successfully.");
+
+	Ok(())
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+use crate::{source::SourceArgs, utility::REPOSITORIES};
+
+pub fn run(source: &SourceArgs) -> Result<()> {
+	let data = source.open()?;
+
+	for repository in REPOSITORIES {
+		// Version lookups only consider the repository segment of the path.
+		match data.version(&format!("common/{repository}")) {
+			Ok(version) => println!("{repository}\t{version}"),
+			Err(ironworks::Error::NotFound(_)) => continue,
+			Err(error) => return Err(error.into()),
+		}
+	}
+
+	Ok(())
+}
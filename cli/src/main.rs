@@ -0,0 +1,103 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use source::SourceArgs;
+
+mod command;
+mod source;
+mod utility;
+
+/// Extract and inspect FFXIV game data.
+#[derive(Debug, Parser)]
+#[clap(name = "ironworks", version)]
+struct Args {
+	#[clap(flatten)]
+	source: SourceArgs,
+
+	#[clap(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Extract files from the game data.
+	Extract(command::extract::Args),
+
+	/// List the files in a SqPack category.
+	Ls(command::ls::Args),
+
+	/// Print the version of each game repository.
+	Version,
+
+	/// Work with Excel sheets.
+	#[clap(subcommand)]
+	Exd(command::exd::Command),
+
+	/// Work with textures.
+	#[clap(subcommand)]
+	Tex(command::tex::Command),
+
+	/// Work with ZiPatch files.
+	#[clap(subcommand)]
+	Patch(command::patch::Command),
+
+	/// Check that every file in the game data can be read.
+	Verify(command::verify::Args),
+}
+
+fn main() -> Result<()> {
+	let args = Args::parse();
+
+	match args.command {
+		Command::Extract(command) => command::extract::run(&args.source, command),
+		Command::Ls(command) => command::ls::run(&args.source, command),
+		Command::Version => command::version::run(&args.source),
+		Command::Exd(command) => command::exd::run(&args.source, command),
+		Command::Tex(command) => command::tex::run(&args.source, command),
+		Command::Patch(command) => command::patch::run(&args.source, command),
+		Command::Verify(command) => command::verify::run(&args.source, command),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use clap::{CommandFactory, Parser};
+
+	use super::{Args, Command};
+
+	#[test]
+	fn verify_args() {
+		Args::command().debug_assert();
+	}
+
+	#[test]
+	fn parse_source() {
+		let args = Args::try_parse_from(["ironworks", "version", "--game", "/ffxiv"]).unwrap();
+		assert!(matches!(args.command, Command::Version));
+
+		Args::try_parse_from(["ironworks", "--game", "a", "--patches", "b", "version"])
+			.unwrap_err();
+	}
+
+	#[test]
+	fn parse_exd() {
+		let args = Args::try_parse_from([
+			"ironworks",
+			"exd",
+			"dump",
+			"Item",
+			"--lang",
+			"en",
+			"--format",
+			"json",
+		])
+		.unwrap();
+		assert!(matches!(args.command, Command::Exd(_)));
+
+		Args::try_parse_from(["ironworks", "exd", "dump", "Item", "--format", "xml"]).unwrap_err();
+	}
+
+	#[test]
+	fn parse_missing_command() {
+		Args::try_parse_from(["ironworks"]).unwrap_err();
+	}
+}
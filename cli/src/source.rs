@@ -0,0 +1,151 @@
+use std::{
+	io::Read,
+	path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use ironworks::{
+	sqpack::{IndexEntry, Install, SqPack},
+	zipatch::{Patch, PatchRepository, View, ZiPatch},
+	Ironworks,
+};
+
+use crate::utility::{patch_version, REPOSITORIES};
+
+#[derive(Debug, clap::Args)]
+pub struct SourceArgs {
+	/// Path to a game installation. Common install locations are searched if
+	/// neither this nor --patches is provided.
+	#[clap(long, global = true)]
+	game: Option<PathBuf>,
+
+	/// Path to a directory of ZiPatch files to read game data from, containing
+	/// a subdirectory per repository, i.e. `ffxiv`, `ex1`.
+	#[clap(long, global = true, conflicts_with = "game")]
+	patches: Option<PathBuf>,
+
+	/// Persist patch lookups to disk, speeding up subsequent runs.
+	#[clap(long, global = true)]
+	persist_lookups: bool,
+}
+
+impl SourceArgs {
+	/// Open the game data specified by the arguments.
+	pub fn open(&self) -> Result<GameData> {
+		match &self.patches {
+			Some(path) => self.open_patches(path, |_| true),
+			None => {
+				let install = match &self.game {
+					Some(path) => Install::at(path),
+					None => Install::search().context("Game path search failed.")?,
+				};
+				Ok(GameData::Install(SqPack::new(install)))
+			}
+		}
+	}
+
+	/// Open the game data as of the specified patch version. Requires --patches.
+	pub fn open_at(&self, version: &str) -> Result<GameData> {
+		let path = self
+			.patches
+			.as_ref()
+			.context("Reading specific versions requires --patches.")?;
+
+		let version = patch_version(version);
+		self.open_patches(path, |patch| patch_version(&patch.name) <= version)
+	}
+
+	fn open_patches(&self, path: &Path, filter: impl Fn(&Patch) -> bool) -> Result<GameData> {
+		let mut zipatch = ZiPatch::new();
+		if self.persist_lookups {
+			zipatch.persist_lookups();
+		}
+
+		let mut builder = zipatch.view();
+		for (id, mut repository) in patch_repositories(path)? {
+			repository.patches.retain(&filter);
+			builder.add_repository(id, repository);
+		}
+
+		Ok(GameData::View(SqPack::new(builder.build())))
+	}
+}
+
+fn patch_repositories(path: &Path) -> Result<Vec<(u8, PatchRepository)>> {
+	let mut repositories = Vec::new();
+	for (id, name) in REPOSITORIES.iter().enumerate() {
+		let repository_path = path.join(name);
+		if repository_path.is_dir() {
+			repositories.push((u8::try_from(id)?, PatchRepository::at(&repository_path)?));
+		}
+	}
+
+	// A directory without any repository subdirectories is assumed to contain
+	// patches for the base repository.
+	if repositories.is_empty() {
+		repositories.push((0, PatchRepository::at(path)?));
+	}
+
+	Ok(repositories)
+}
+
+/// Game data being operated on, read from either an installation or a set of patches.
+#[derive(Debug)]
+pub enum GameData {
+	Install(SqPack<Install>),
+	View(SqPack<View>),
+}
+
+impl GameData {
+	/// Get the version of the repository containing `path`.
+	pub fn version(&self, path: &str) -> Result<String, ironworks::Error> {
+		match self {
+			Self::Install(sqpack) => sqpack.version(path),
+			Self::View(sqpack) => sqpack.version(path),
+		}
+	}
+
+	/// Look up the index entry for the file at `path`.
+	pub fn entry(&self, path: &str) -> Result<IndexEntry, ironworks::Error> {
+		match self {
+			Self::Install(sqpack) => sqpack.entry(path),
+			Self::View(sqpack) => sqpack.entry(path),
+		}
+	}
+
+	/// List the files in the category containing `path`.
+	pub fn entries(&self, path: &str) -> Result<Vec<IndexEntry>, ironworks::Error> {
+		match self {
+			Self::Install(sqpack) => sqpack.entries(path),
+			Self::View(sqpack) => sqpack.entries(path),
+		}
+	}
+
+	/// Read the full contents of the file at `path`.
+	pub fn read(&self, path: &str) -> Result<Vec<u8>> {
+		let mut buffer = Vec::new();
+		match self {
+			Self::Install(sqpack) => sqpack.file(path)?.read_to_end(&mut buffer)?,
+			Self::View(sqpack) => sqpack.file(path)?.read_to_end(&mut buffer)?,
+		};
+		Ok(buffer)
+	}
+
+	/// Read the full contents of the file listed by an index entry.
+	pub fn read_entry(&self, entry: &IndexEntry) -> Result<Vec<u8>> {
+		let mut buffer = Vec::new();
+		match self {
+			Self::Install(sqpack) => sqpack.entry_file(entry)?.read_to_end(&mut buffer)?,
+			Self::View(sqpack) => sqpack.entry_file(entry)?.read_to_end(&mut buffer)?,
+		};
+		Ok(buffer)
+	}
+
+	/// Build an ironworks instance reading from this game data.
+	pub fn into_ironworks(self) -> Ironworks {
+		match self {
+			Self::Install(sqpack) => Ironworks::new().with_resource(sqpack),
+			Self::View(sqpack) => Ironworks::new().with_resource(sqpack),
+		}
+	}
+}
@@ -0,0 +1,28 @@
+// Mirrors the category table used by SqPack. Gaps in the table are omitted.
+pub const CATEGORIES: &[&str] = &[
+	"common",
+	"bgcommon",
+	"bg",
+	"cut",
+	"chara",
+	"shader",
+	"ui",
+	"sound",
+	"vfx",
+	"ui_script",
+	"exd",
+	"game_script",
+	"music",
+	"_sqpack_test",
+	"_debug",
+];
+
+pub const REPOSITORIES: &[&str] = &[
+	"ffxiv", "ex1", "ex2", "ex3", "ex4", "ex5", "ex6", "ex7", "ex8", "ex9",
+];
+
+/// Strip the patch kind prefix from a patch name, leaving a string-sortable
+/// version. Patches are named like `D2023.01.01.0000.0000`.
+pub fn patch_version(name: &str) -> &str {
+	name.trim_start_matches(['D', 'H'])
+}
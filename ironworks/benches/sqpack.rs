@@ -0,0 +1,159 @@
+use std::io::Cursor;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use ironworks::{
+	sqpack::{self, Location, SqPack},
+	Error, ErrorValue,
+};
+
+const SIZES: &[usize] = &[1_000, 10_000, 100_000, 500_000];
+
+/// SqPack resource serving a single synthetic index containing `count` files.
+struct SyntheticResource {
+	index: Vec<u8>,
+}
+
+impl SyntheticResource {
+	fn new(count: usize) -> Self {
+		Self {
+			index: build_index(count),
+		}
+	}
+}
+
+impl sqpack::Resource for SyntheticResource {
+	fn version(&self, _repository: u8) -> Result<String, Error> {
+		Ok("synthetic".into())
+	}
+
+	type Index = Cursor<Vec<u8>>;
+	fn index(&self, _repository: u8, _category: u8, chunk: u8) -> Result<Self::Index, Error> {
+		match chunk {
+			0 => Ok(Cursor::new(self.index.clone())),
+			_ => Err(Error::NotFound(ErrorValue::Other(format!("chunk {chunk}")))),
+		}
+	}
+
+	type Index2 = Cursor<Vec<u8>>;
+	fn index2(&self, _repository: u8, _category: u8, chunk: u8) -> Result<Self::Index2, Error> {
+		Err(Error::NotFound(ErrorValue::Other(format!("chunk {chunk}"))))
+	}
+
+	type File = Cursor<Vec<u8>>;
+	fn file(
+		&self,
+		_repository: u8,
+		_category: u8,
+		_location: Location,
+	) -> Result<Self::File, Error> {
+		// Standard file with no blocks.
+		let mut bytes = Vec::new();
+		bytes.extend(24u32.to_le_bytes());
+		bytes.extend(2u32.to_le_bytes());
+		bytes.resize(24, 0);
+		Ok(Cursor::new(bytes))
+	}
+}
+
+fn path(index: usize) -> String {
+	format!("common/synthetic/file_{index}.bin")
+}
+
+fn hash(path: &str) -> u64 {
+	// Index1 hashes use CRC32 without the final inversion.
+	let crc = |segment: &str| u64::from(!crc32fast::hash(segment.as_bytes()));
+	let (directory, file) = path.rsplit_once('/').unwrap();
+	crc(directory) << 32 | crc(file)
+}
+
+fn build_index(count: usize) -> Vec<u8> {
+	const HEADER_SIZE: u32 = 0x400;
+	const ENTRIES_OFFSET: u32 = 0x800;
+
+	fn section(bytes: &mut Vec<u8>, offset: u32, size: u32) {
+		bytes.extend(offset.to_le_bytes());
+		bytes.extend(size.to_le_bytes());
+		bytes.extend([0u8; 64]);
+	}
+
+	// Real indexes are sorted by hash.
+	let mut hashes = (0..count)
+		.map(|index| hash(&path(index)))
+		.collect::<Vec<_>>();
+	hashes.sort_unstable();
+
+	let mut bytes = Vec::new();
+
+	// SqPack header.
+	bytes.extend(b"SqPack\0\0");
+	bytes.extend([0u8; 4]);
+	bytes.extend(HEADER_SIZE.to_le_bytes());
+	bytes.resize(HEADER_SIZE as usize, 0);
+
+	// Index header.
+	let entries_size = u32::try_from(count * 16).unwrap();
+	bytes.extend(HEADER_SIZE.to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	section(&mut bytes, ENTRIES_OFFSET, entries_size);
+	bytes.extend(1u32.to_le_bytes());
+	for _ in 0..3 {
+		section(&mut bytes, 0, 0);
+	}
+	bytes.resize(ENTRIES_OFFSET as usize, 0);
+
+	// Entries, each file laid out sequentially in the first dat.
+	for (index, hash) in hashes.into_iter().enumerate() {
+		let offset = u32::try_from(index * 0x80).unwrap();
+		bytes.extend(hash.to_le_bytes());
+		bytes.extend((offset / 8).to_le_bytes());
+		bytes.extend([0u8; 4]);
+	}
+
+	bytes
+}
+
+fn find(criterion: &mut Criterion) {
+	let mut group = criterion.benchmark_group("sqpack/find");
+
+	for &size in SIZES {
+		let sqpack = SqPack::new(SyntheticResource::new(size));
+		let paths = (0..size).step_by(size / 100).map(path).collect::<Vec<_>>();
+
+		// Prime the index cache so only lookups are measured.
+		sqpack.file(&paths[0]).unwrap();
+
+		group.bench_with_input(BenchmarkId::new("hit", size), &paths, |bencher, paths| {
+			bencher.iter(|| {
+				for path in paths {
+					black_box(sqpack.file(path).unwrap());
+				}
+			})
+		});
+
+		group.bench_with_input(BenchmarkId::new("miss", size), &size, |bencher, _| {
+			bencher.iter(|| black_box(sqpack.file("common/synthetic/missing.bin").unwrap_err()))
+		});
+	}
+
+	group.finish();
+}
+
+fn load(criterion: &mut Criterion) {
+	let mut group = criterion.benchmark_group("sqpack/load");
+	group.sample_size(10);
+
+	for &size in SIZES {
+		group.bench_function(BenchmarkId::from_parameter(size), |bencher| {
+			bencher.iter_batched(
+				|| SqPack::new(SyntheticResource::new(size)),
+				|sqpack| black_box(sqpack.file(&path(0)).unwrap()),
+				BatchSize::LargeInput,
+			)
+		});
+	}
+
+	group.finish();
+}
+
+criterion_group!(benches, find, load);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+use std::{
+	fs,
+	io::{self, BufReader, Read, Seek},
+	path::Path,
+};
+
+use derivative::Derivative;
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	ironworks::FileStream,
+	Resource,
+};
+
+use super::{tar::TarArchive, zip::ZipArchive};
+
+const DEFAULT_VERSION_PATH: &str = "ffxivgame.ver";
+
+const ZIP_MAGIC: &[u8] = b"PK";
+
+/// Resource for reading game files out of a `.zip`, `.tar`, or `.tar.gz`
+/// archive. Entries in the archive are expected to be laid out by game path,
+/// i.e. `exd/root.exl`.
+///
+/// The version of files in the archive is read from a manifest entry, which by
+/// default is `ffxivgame.ver` at the root of the archive.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Archive {
+	#[derivative(Debug = "ignore")]
+	kind: ArchiveKind,
+	version_path: String,
+}
+
+enum ArchiveKind {
+	Zip(ZipArchive),
+	Tar(TarArchive),
+}
+
+impl Archive {
+	/// Open the archive at the specified path. The format of the archive is
+	/// detected from its contents.
+	pub fn open(path: &Path) -> Result<Self> {
+		let reader = BufReader::new(fs::File::open(path)?);
+		Self::new(reader)
+	}
+
+	/// Build an archive resource from the given reader. The format of the
+	/// archive is detected from its contents.
+	pub fn new(mut reader: impl Read + Seek + Send + 'static) -> Result<Self> {
+		let mut magic = [0u8; 2];
+		let is_zip = match reader.read_exact(&mut magic) {
+			Ok(()) => magic == ZIP_MAGIC,
+			Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => false,
+			Err(error) => return Err(error.into()),
+		};
+		reader.rewind()?;
+
+		let kind = match is_zip {
+			true => ArchiveKind::Zip(ZipArchive::new(reader)?),
+			false => ArchiveKind::Tar(TarArchive::new(reader)?),
+		};
+
+		Ok(Self {
+			kind,
+			version_path: DEFAULT_VERSION_PATH.into(),
+		})
+	}
+
+	/// Set the path of the manifest entry the archive's version is read from.
+	#[must_use]
+	pub fn with_version_path(mut self, path: impl Into<String>) -> Self {
+		self.version_path = normalize(&path.into());
+		self
+	}
+
+	fn contains(&self, path: &str) -> bool {
+		match &self.kind {
+			ArchiveKind::Zip(archive) => archive.contains(path),
+			ArchiveKind::Tar(archive) => archive.contains(path),
+		}
+	}
+
+	fn read(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		match &self.kind {
+			ArchiveKind::Zip(archive) => archive.read(path),
+			ArchiveKind::Tar(archive) => archive.read(path),
+		}
+	}
+}
+
+impl Resource for Archive {
+	fn version(&self, path: &str) -> Result<String> {
+		let path = normalize(path);
+		if !self.contains(&path) {
+			return Err(Error::NotFound(ErrorValue::Path(path)));
+		}
+
+		let mut version = String::new();
+		self.read(&self.version_path)
+			.map_err(|error| match error {
+				Error::NotFound(_) => Error::Invalid(
+					ErrorValue::Path(self.version_path.clone()),
+					"archive does not contain a version manifest".into(),
+				),
+				other => other,
+			})?
+			.read_to_string(&mut version)?;
+
+		Ok(version.trim().to_string())
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		self.read(&normalize(path))
+	}
+}
+
+/// Normalize a path to the form used for lookups. Game paths are
+/// case-insensitive, and archive tools commonly prefix entries with `./`.
+pub fn normalize(path: &str) -> String {
+	path.trim_start_matches("./")
+		.trim_start_matches('/')
+		.to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::{Cursor, Write};
+
+	use flate2::{write::GzEncoder, Compression};
+
+	use crate::{Error, ErrorValue, Ironworks};
+
+	use super::Archive;
+
+	const FILES: &[(&str, &str)] = &[
+		("ffxivgame.ver", "2023.01.01.0000.0000\n"),
+		("./exd/root.exl", "EXLT,2\n"),
+		("exd/Item.exh", "header"),
+	];
+
+	fn build_zip() -> Vec<u8> {
+		let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+		for (path, contents) in FILES {
+			writer.start_file(*path, Default::default()).unwrap();
+			writer.write_all(contents.as_bytes()).unwrap();
+		}
+		writer.finish().unwrap().into_inner()
+	}
+
+	fn build_tar() -> Vec<u8> {
+		let mut builder = tar::Builder::new(Vec::new());
+		for (path, contents) in FILES {
+			let mut header = tar::Header::new_gnu();
+			header.set_size(contents.len().try_into().unwrap());
+			header.set_cksum();
+			builder
+				.append_data(&mut header, path, contents.as_bytes())
+				.unwrap();
+		}
+		builder.into_inner().unwrap()
+	}
+
+	fn gzip(bytes: &[u8]) -> Vec<u8> {
+		let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(bytes).unwrap();
+		encoder.finish().unwrap()
+	}
+
+	fn assert_archive(bytes: Vec<u8>) {
+		let ironworks = Ironworks::new().with_resource(Archive::new(Cursor::new(bytes)).unwrap());
+
+		assert_eq!(
+			ironworks.file::<Vec<u8>>("exd/root.exl").unwrap(),
+			b"EXLT,2\n"
+		);
+		assert_eq!(
+			ironworks.file::<Vec<u8>>("exd/item.exh").unwrap(),
+			b"header"
+		);
+		assert_eq!(
+			ironworks.version("exd/root.exl").unwrap(),
+			"2023.01.01.0000.0000"
+		);
+		assert!(matches!(
+			ironworks.file::<Vec<u8>>("exd/missing.exh"),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+	}
+
+	#[test]
+	fn zip() {
+		assert_archive(build_zip());
+	}
+
+	#[test]
+	fn tar() {
+		assert_archive(build_tar());
+	}
+
+	#[test]
+	fn tar_gz() {
+		assert_archive(gzip(&build_tar()));
+	}
+}
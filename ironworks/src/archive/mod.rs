@@ -0,0 +1,7 @@
+//! Adapters to allow working with game files extracted into zip or tar archives.
+
+mod archive;
+mod tar;
+mod zip;
+
+pub use archive::Archive;
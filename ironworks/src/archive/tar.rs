@@ -0,0 +1,77 @@
+use std::{
+	collections::HashMap,
+	io::{BufRead, BufReader, Cursor, Read},
+	sync::Arc,
+};
+
+use flate2::bufread::GzDecoder;
+use tar::{Archive as TarReader, EntryType};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	ironworks::FileStream,
+};
+
+use super::archive::normalize;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+pub struct TarArchive {
+	entries: HashMap<String, Arc<[u8]>>,
+}
+
+impl TarArchive {
+	pub fn new(reader: impl Read) -> Result<Self> {
+		// Tar archives do not have a central directory, and gzip streams can't be
+		// seeked - read the full archive into memory up front.
+		let mut reader = BufReader::new(reader);
+		let entries = match reader.fill_buf()?.starts_with(GZIP_MAGIC) {
+			true => read_entries(GzDecoder::new(reader))?,
+			false => read_entries(reader)?,
+		};
+
+		Ok(Self { entries })
+	}
+
+	pub fn contains(&self, path: &str) -> bool {
+		self.entries.contains_key(path)
+	}
+
+	pub fn read(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let data = self
+			.entries
+			.get(path)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+
+		Ok(Box::new(Cursor::new(data.clone())))
+	}
+}
+
+fn read_entries(reader: impl Read) -> Result<HashMap<String, Arc<[u8]>>> {
+	let mut archive = TarReader::new(reader);
+
+	archive
+		.entries()?
+		.filter_map(|entry| {
+			let mut entry = match entry {
+				Ok(entry) => entry,
+				Err(error) => return Some(Err(error.into())),
+			};
+
+			if entry.header().entry_type() != EntryType::Regular {
+				return None;
+			}
+
+			Some(read_entry(&mut entry))
+		})
+		.collect()
+}
+
+fn read_entry(entry: &mut tar::Entry<impl Read>) -> Result<(String, Arc<[u8]>)> {
+	let path = normalize(&entry.path()?.to_string_lossy());
+
+	let mut buffer = Vec::with_capacity(entry.size().try_into().unwrap_or(0));
+	entry.read_to_end(&mut buffer)?;
+
+	Ok((path, buffer.into()))
+}
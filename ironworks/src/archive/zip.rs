@@ -0,0 +1,75 @@
+use std::{
+	collections::HashMap,
+	io::{Cursor, Read, Seek},
+	sync::Mutex,
+};
+
+use zip::{result::ZipError, ZipArchive as ZipReader};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	ironworks::FileStream,
+};
+
+use super::archive::normalize;
+
+trait ZipStream: Read + Seek + Send {}
+impl<T> ZipStream for T where T: Read + Seek + Send {}
+
+pub struct ZipArchive {
+	reader: Mutex<ZipReader<Box<dyn ZipStream>>>,
+	entries: HashMap<String, usize>,
+}
+
+impl ZipArchive {
+	pub fn new(reader: impl Read + Seek + Send + 'static) -> Result<Self> {
+		let mut reader =
+			ZipReader::new(Box::new(reader) as Box<dyn ZipStream>).map_err(zip_error)?;
+
+		// Zip entry names are case-sensitive, build a lookup of normalized paths
+		// to entry indices so lookups match the game's behavior.
+		let entries = (0..reader.len())
+			.map(|index| -> Result<_> {
+				let file = reader.by_index_raw(index).map_err(zip_error)?;
+				Ok((file.is_file(), normalize(file.name()), index))
+			})
+			.filter_map(|result| match result {
+				Ok((false, ..)) => None,
+				Ok((true, name, index)) => Some(Ok((name, index))),
+				Err(error) => Some(Err(error)),
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(Self {
+			reader: Mutex::new(reader),
+			entries,
+		})
+	}
+
+	pub fn contains(&self, path: &str) -> bool {
+		self.entries.contains_key(path)
+	}
+
+	pub fn read(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let index = *self
+			.entries
+			.get(path)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+
+		let mut reader = self.reader.lock().unwrap();
+		let mut file = reader.by_index(index).map_err(zip_error)?;
+
+		let mut buffer = Vec::with_capacity(file.size().try_into().unwrap_or(0));
+		file.read_to_end(&mut buffer)?;
+
+		Ok(Box::new(Cursor::new(buffer)))
+	}
+}
+
+fn zip_error(error: ZipError) -> Error {
+	match error {
+		ZipError::Io(error) => error.into(),
+		ZipError::FileNotFound => Error::NotFound(ErrorValue::Other("zip entry".into())),
+		other => Error::Resource(other.into()),
+	}
+}
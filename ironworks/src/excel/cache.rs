@@ -0,0 +1,272 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::{Arc, Mutex},
+};
+
+use getset::CopyGetters;
+
+use crate::file::exd;
+
+use super::language::Language;
+
+type PageKey = (u32, Language);
+
+/// Limits on the memory used by cached Excel pages. Once a limit is exceeded,
+/// the least recently used pages are evicted.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CacheLimits {
+	pub pages: Option<usize>,
+	pub bytes: Option<usize>,
+}
+
+/// Snapshot of the statistics of an Excel database's page cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, CopyGetters)]
+#[get_copy = "pub"]
+pub struct CacheStats {
+	/// Number of page reads served from the cache.
+	hits: u64,
+	/// Number of page reads that required reading the page from disk.
+	misses: u64,
+	/// Number of pages evicted to remain within the configured limits.
+	evictions: u64,
+	/// Number of pages currently cached.
+	pages: usize,
+	/// Size in bytes of the data of pages currently cached.
+	bytes: usize,
+}
+
+// LRU cache of pages across all sheets of a database.
+#[derive(Debug, Default)]
+pub(crate) struct PageCache {
+	limits: CacheLimits,
+	state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	sheets: HashMap<String, HashMap<PageKey, Entry>>,
+	// Cached pages by their last use, oldest first.
+	order: BTreeMap<u64, (String, PageKey)>,
+	tick: u64,
+	stats: CacheStats,
+}
+
+#[derive(Debug)]
+struct Entry {
+	page: Arc<exd::ExcelData>,
+	size: usize,
+	used: u64,
+}
+
+impl PageCache {
+	pub fn new(limits: CacheLimits) -> Self {
+		Self {
+			limits,
+			state: Default::default(),
+		}
+	}
+
+	pub fn try_get_or_insert<E>(
+		&self,
+		sheet: &str,
+		key: PageKey,
+		build: impl FnOnce() -> Result<exd::ExcelData, E>,
+	) -> Result<Arc<exd::ExcelData>, E> {
+		let mut state = self.state.lock().unwrap();
+		if let Some(page) = state.get(sheet, key) {
+			state.stats.hits += 1;
+			return Ok(page);
+		}
+		state.stats.misses += 1;
+		drop(state);
+
+		// Build outside the lock, such that lookups of other pages are not blocked
+		// in the meantime. Should two builds of one page race, the first is kept.
+		let page = Arc::new(build()?);
+
+		let mut state = self.state.lock().unwrap();
+		if let Some(page) = state.get(sheet, key) {
+			return Ok(page);
+		}
+		state.insert(sheet, key, page.clone());
+		state.evict_to(self.limits);
+
+		Ok(page)
+	}
+
+	pub fn clear(&self) {
+		let mut state = self.state.lock().unwrap();
+		for (_, (sheet, key)) in std::mem::take(&mut state.order) {
+			state.remove(&sheet, key);
+		}
+	}
+
+	pub fn evict(&self, sheet: &str) {
+		let mut state = self.state.lock().unwrap();
+		let Some(pages) = state.sheets.remove(sheet) else {
+			return;
+		};
+		for entry in pages.values() {
+			state.order.remove(&entry.used);
+			state.stats.pages -= 1;
+			state.stats.bytes -= entry.size;
+		}
+	}
+
+	pub fn stats(&self) -> CacheStats {
+		self.state.lock().unwrap().stats
+	}
+}
+
+impl State {
+	// Fetch a cached page, marking it as recently used.
+	fn get(&mut self, sheet: &str, key: PageKey) -> Option<Arc<exd::ExcelData>> {
+		self.tick += 1;
+		let tick = self.tick;
+
+		let entry = self.sheets.get_mut(sheet)?.get_mut(&key)?;
+		let order = self.order.remove(&entry.used).unwrap();
+		self.order.insert(tick, order);
+		entry.used = tick;
+
+		Some(entry.page.clone())
+	}
+
+	fn insert(&mut self, sheet: &str, key: PageKey, page: Arc<exd::ExcelData>) {
+		self.tick += 1;
+		let size = page.bytes().len();
+
+		self.sheets.entry(sheet.to_string()).or_default().insert(
+			key,
+			Entry {
+				page,
+				size,
+				used: self.tick,
+			},
+		);
+		self.order.insert(self.tick, (sheet.to_string(), key));
+
+		self.stats.pages += 1;
+		self.stats.bytes += size;
+	}
+
+	fn remove(&mut self, sheet: &str, key: PageKey) {
+		let Some(pages) = self.sheets.get_mut(sheet) else {
+			return;
+		};
+		if let Some(entry) = pages.remove(&key) {
+			self.stats.pages -= 1;
+			self.stats.bytes -= entry.size;
+		}
+		if pages.is_empty() {
+			self.sheets.remove(sheet);
+		}
+	}
+
+	// Evict the least recently used pages until within the provided limits. The
+	// most recently used page is always retained.
+	fn evict_to(&mut self, limits: CacheLimits) {
+		let exceeded = |stats: &CacheStats| {
+			matches!(limits.pages, Some(pages) if stats.pages > pages)
+				|| matches!(limits.bytes, Some(bytes) if stats.bytes > bytes)
+		};
+
+		while self.order.len() > 1 && exceeded(&self.stats) {
+			let (_, (sheet, key)) = self.order.pop_first().unwrap();
+			self.remove(&sheet, key);
+			self.stats.evictions += 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::convert::Infallible;
+
+	use crate::{excel::Language, file::exd::ExcelData};
+
+	use super::{CacheLimits, PageCache};
+
+	fn page(size: usize) -> Result<ExcelData, Infallible> {
+		let mut page = ExcelData::new();
+		page.set_row(0, vec![0; size]).unwrap();
+		Ok(page)
+	}
+
+	#[test]
+	fn lru_pages() {
+		let cache = PageCache::new(CacheLimits {
+			pages: Some(2),
+			bytes: None,
+		});
+		let key = |start_id| (start_id, Language::None);
+
+		cache.try_get_or_insert("a", key(0), || page(1)).unwrap();
+		cache.try_get_or_insert("a", key(1), || page(1)).unwrap();
+		cache.try_get_or_insert("a", key(0), || page(1)).unwrap();
+		cache.try_get_or_insert("b", key(0), || page(1)).unwrap();
+
+		// Page 1 of a was least recently used, and should have been evicted.
+		let mut built = false;
+		cache
+			.try_get_or_insert("a", key(1), || {
+				built = true;
+				page(1)
+			})
+			.unwrap();
+		assert!(built);
+
+		let stats = cache.stats();
+		assert_eq!(stats.hits(), 1);
+		assert_eq!(stats.misses(), 4);
+		assert_eq!(stats.evictions(), 2);
+		assert_eq!(stats.pages(), 2);
+	}
+
+	#[test]
+	fn bytes_and_eviction() {
+		let size = page(8).unwrap().bytes().len();
+		let cache = PageCache::new(CacheLimits {
+			pages: None,
+			bytes: Some(size * 2),
+		});
+
+		for start_id in 0..3 {
+			let key = (start_id, Language::None);
+			cache.try_get_or_insert("a", key, || page(8)).unwrap();
+		}
+		cache
+			.try_get_or_insert("b", (0, Language::None), || page(8))
+			.unwrap();
+		assert_eq!(cache.stats().pages(), 2);
+		assert_eq!(cache.stats().bytes(), size * 2);
+
+		cache.evict("a");
+		assert_eq!(cache.stats().pages(), 1);
+
+		cache.clear();
+		assert_eq!(cache.stats().pages(), 0);
+		assert_eq!(cache.stats().bytes(), 0);
+		assert_eq!(cache.stats().evictions(), 2);
+	}
+
+	#[test]
+	fn racing_builds() {
+		let cache = PageCache::new(CacheLimits::default());
+		let key = (0, Language::None);
+
+		// Insert the page while the outer build is in progress, as a racing thread
+		// would. Each call should be counted once.
+		let cached = cache
+			.try_get_or_insert("a", key, || {
+				cache.try_get_or_insert("a", key, || page(2)).unwrap();
+				page(1)
+			})
+			.unwrap();
+		assert_eq!(cached.bytes().len(), page(2).unwrap().bytes().len());
+
+		let stats = cache.stats();
+		assert_eq!(stats.hits() + stats.misses(), 2);
+		assert_eq!(stats.pages(), 1);
+	}
+}
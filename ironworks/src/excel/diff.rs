@@ -0,0 +1,444 @@
+use std::collections::BTreeMap;
+
+use getset::{CopyGetters, Getters};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::exh::{ColumnDefinition, ColumnKind},
+};
+
+use super::{
+	excel::Excel,
+	field::Field,
+	language::Language,
+	row::Row,
+	sheet::{RowConfig, Sheet},
+};
+
+/// Differences in a sheet between two Excel databases.
+#[derive(Debug, Default, Getters)]
+#[get = "pub"]
+pub struct SheetDiff {
+	/// Columns present only in the new sheet.
+	columns_added: Vec<ColumnDefinition>,
+	/// Columns present only in the old sheet.
+	columns_removed: Vec<ColumnDefinition>,
+
+	/// IDs of (sub)rows present only in the new sheet, as `(row, subrow)`.
+	rows_added: Vec<(u32, u16)>,
+	/// IDs of (sub)rows present only in the old sheet, as `(row, subrow)`.
+	rows_removed: Vec<(u32, u16)>,
+	/// (Sub)rows present in both sheets with differing field values.
+	rows_changed: Vec<RowDiff>,
+}
+
+impl SheetDiff {
+	/// Check if the sheets are equivalent.
+	pub fn is_empty(&self) -> bool {
+		self.columns_added.is_empty()
+			&& self.columns_removed.is_empty()
+			&& self.rows_added.is_empty()
+			&& self.rows_removed.is_empty()
+			&& self.rows_changed.is_empty()
+	}
+}
+
+/// Field-level changes to a single (sub)row.
+#[derive(Debug, Getters, CopyGetters)]
+pub struct RowDiff {
+	/// ID of the changed row.
+	#[get_copy = "pub"]
+	row_id: u32,
+	/// Subrow ID of the changed row.
+	#[get_copy = "pub"]
+	subrow_id: u16,
+	/// Changes to the fields of the row.
+	#[get = "pub"]
+	changes: Vec<FieldChange>,
+}
+
+/// A change to the value of a single field.
+#[derive(Debug, Getters, CopyGetters)]
+pub struct FieldChange {
+	/// Definition of the changed column.
+	#[get = "pub"]
+	column: ColumnDefinition,
+	/// Index of the changed column within the new sheet.
+	#[get_copy = "pub"]
+	index: usize,
+	/// Language the change was found in. String fields are compared in every
+	/// language shared by both sheets, other fields only in the shared language
+	/// with the lowest ID.
+	#[get_copy = "pub"]
+	language: Language,
+	/// Value of the field in the old sheet.
+	#[get = "pub"]
+	old: Field,
+	/// Value of the field in the new sheet.
+	#[get = "pub"]
+	new: Field,
+}
+
+impl FieldChange {
+	/// Word-level differences between the text of the old and new values, with
+	/// SeString payloads omitted. Returns `None` if the field is not a string,
+	/// or either string could not be parsed.
+	pub fn text_diff(&self) -> Option<Vec<TextChange>> {
+		match (&self.old, &self.new) {
+			(Field::String(old), Field::String(new)) => {
+				Some(text_diff(&old.text().ok()?, &new.text().ok()?))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// A span of text within a text diff.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextChange {
+	Equal(String),
+	Removed(String),
+	Added(String),
+}
+
+/// Compare a sheet between two Excel databases, such as two game versions.
+///
+/// Columns are matched by their definition, such that a column moving within
+/// the row structure is reported as both a removal and an addition. Fields are
+/// only compared for columns present in both sheets.
+pub fn diff(old: &Excel, new: &Excel, sheet: &str) -> Result<SheetDiff> {
+	diff_sheets(&old.sheet(sheet)?, &new.sheet(sheet)?)
+}
+
+fn diff_sheets(old: &Sheet<&str>, new: &Sheet<&str>) -> Result<SheetDiff> {
+	let mut diff = SheetDiff::default();
+
+	// Match columns by definition, pairing duplicate definitions in order.
+	let old_columns = old.columns()?;
+	let new_columns = new.columns()?;
+	let mut unmatched = old_columns.iter().enumerate().collect::<Vec<_>>();
+	let mut shared = vec![];
+	for (new_index, column) in new_columns.iter().enumerate() {
+		match unmatched.iter().position(|(_, old)| *old == column) {
+			Some(position) => shared.push((unmatched.remove(position).0, new_index)),
+			None => diff.columns_added.push(column.clone()),
+		}
+	}
+	diff.columns_removed = unmatched
+		.into_iter()
+		.map(|(_, column)| column.clone())
+		.collect();
+
+	let old_languages = old.languages()?;
+	let mut languages = new
+		.languages()?
+		.into_iter()
+		.filter(|language| old_languages.contains(language))
+		.collect::<Vec<_>>();
+	languages.sort_by_key(|&language| u8::from(language));
+	if languages.is_empty() {
+		return Err(Error::Invalid(
+			ErrorValue::Sheet(new.name()),
+			"sheets do not share any languages".into(),
+		));
+	}
+
+	let mut changes = BTreeMap::<(u32, u16), Vec<FieldChange>>::new();
+	for (language_index, &language) in languages.iter().enumerate() {
+		let old_rows = read_rows(old, language)?;
+		let new_rows = read_rows(new, language)?;
+
+		// Row presence is independent of language, only check it once.
+		if language_index == 0 {
+			diff.rows_added = new_rows
+				.keys()
+				.filter(|id| !old_rows.contains_key(id))
+				.copied()
+				.collect();
+			diff.rows_removed = old_rows
+				.keys()
+				.filter(|id| !new_rows.contains_key(id))
+				.copied()
+				.collect();
+		}
+
+		for (id, new_row) in &new_rows {
+			let Some(old_row) = old_rows.get(id) else {
+				continue;
+			};
+
+			for &(old_index, new_index) in &shared {
+				let column = &new_columns[new_index];
+				if language_index > 0 && column.kind() != ColumnKind::String {
+					continue;
+				}
+
+				let old_field = old_row.field(old_index)?;
+				let new_field = new_row.field(new_index)?;
+				if fields_equal(&old_field, &new_field) {
+					continue;
+				}
+
+				changes.entry(*id).or_default().push(FieldChange {
+					column: column.clone(),
+					index: new_index,
+					language,
+					old: old_field,
+					new: new_field,
+				});
+			}
+		}
+	}
+
+	diff.rows_changed = changes
+		.into_iter()
+		.map(|((row_id, subrow_id), changes)| RowDiff {
+			row_id,
+			subrow_id,
+			changes,
+		})
+		.collect();
+
+	Ok(diff)
+}
+
+fn read_rows(sheet: &Sheet<&str>, language: Language) -> Result<BTreeMap<(u32, u16), Row>> {
+	let config = RowConfig {
+		language: Some(language),
+		..Default::default()
+	};
+
+	sheet
+		.try_iter_with_options(config)
+		.map(|row| {
+			let row = row?;
+			Ok(((row.row_id(), row.subrow_id()), row))
+		})
+		.collect()
+}
+
+fn fields_equal(a: &Field, b: &Field) -> bool {
+	use Field as F;
+	match (a, b) {
+		(F::String(a), F::String(b)) => a.as_bytes() == b.as_bytes(),
+		(F::Bool(a), F::Bool(b)) => a == b,
+		(F::I8(a), F::I8(b)) => a == b,
+		(F::I16(a), F::I16(b)) => a == b,
+		(F::I32(a), F::I32(b)) => a == b,
+		(F::I64(a), F::I64(b)) => a == b,
+		(F::U8(a), F::U8(b)) => a == b,
+		(F::U16(a), F::U16(b)) => a == b,
+		(F::U32(a), F::U32(b)) => a == b,
+		(F::U64(a), F::U64(b)) => a == b,
+		// Compare bitwise, so NaN values that have not changed are not reported.
+		(F::F32(a), F::F32(b)) => a.to_bits() == b.to_bits(),
+		_ => false,
+	}
+}
+
+fn text_diff(old: &str, new: &str) -> Vec<TextChange> {
+	let old = tokenize(old);
+	let new = tokenize(new);
+
+	// Longest common subsequence lengths of each pair of token suffixes.
+	let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+	for i in (0..old.len()).rev() {
+		for j in (0..new.len()).rev() {
+			lengths[i][j] = match old[i] == new[j] {
+				true => lengths[i + 1][j + 1] + 1,
+				false => lengths[i + 1][j].max(lengths[i][j + 1]),
+			};
+		}
+	}
+
+	let mut changes = Vec::<TextChange>::new();
+	let mut push = |change: fn(String) -> TextChange, token: &str| {
+		let merged = match (changes.last_mut(), change(String::new())) {
+			(Some(TextChange::Equal(text)), TextChange::Equal(_))
+			| (Some(TextChange::Removed(text)), TextChange::Removed(_))
+			| (Some(TextChange::Added(text)), TextChange::Added(_)) => {
+				text.push_str(token);
+				true
+			}
+			_ => false,
+		};
+		if !merged {
+			changes.push(change(token.to_string()));
+		}
+	};
+
+	let (mut i, mut j) = (0, 0);
+	while i < old.len() || j < new.len() {
+		if i < old.len() && j < new.len() && old[i] == new[j] {
+			push(TextChange::Equal, old[i]);
+			i += 1;
+			j += 1;
+		} else if j < new.len() && (i == old.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+			push(TextChange::Added, new[j]);
+			j += 1;
+		} else {
+			push(TextChange::Removed, old[i]);
+			i += 1;
+		}
+	}
+
+	changes
+}
+
+// Split text into alternating runs of whitespace and non-whitespace.
+fn tokenize(text: &str) -> Vec<&str> {
+	let mut tokens = vec![];
+	let mut start = 0;
+	let mut previous = None;
+	for (index, character) in text.char_indices() {
+		let whitespace = character.is_whitespace();
+		if previous == Some(!whitespace) {
+			tokens.push(&text[start..index]);
+			start = index;
+		}
+		previous = Some(whitespace);
+	}
+	if start < text.len() {
+		tokens.push(&text[start..]);
+	}
+	tokens
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		excel::{fixture::Fixture, Excel, Field, Language, RowWriter},
+		file::{
+			exd::ExcelData,
+			exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		},
+		sestring::SeString,
+		Ironworks,
+	};
+
+	use super::{diff, text_diff, TextChange};
+
+	// Build a sheet with a string and a u32 column, and rows of
+	// `(id, english, japanese, value)`.
+	fn ironworks(columns: &[ColumnDefinition], rows: &[(u32, &str, &str, u32)]) -> Ironworks {
+		let header = Arc::new(ExcelHeader::new(
+			SheetKind::Default,
+			8,
+			columns.to_vec(),
+			vec![PageDefinition::new(0, 10)],
+			rows.len().try_into().unwrap(),
+			vec![Language::English.into(), Language::Japanese.into()],
+		));
+
+		let mut fixture = Fixture::new();
+		fixture.header("Sheet", &header);
+
+		for language in [Language::English, Language::Japanese] {
+			let mut data = ExcelData::new();
+			for &(row_id, english, japanese, value) in rows {
+				let text = match language {
+					Language::English => english,
+					_ => japanese,
+				};
+				let mut writer = RowWriter::new(header.clone());
+				for (index, column) in columns.iter().enumerate() {
+					let field = match column.kind() {
+						ColumnKind::String => Field::String(SeString::try_from(text).unwrap()),
+						ColumnKind::Int32 => Field::I32(value.try_into().unwrap()),
+						_ => Field::U32(value),
+					};
+					writer.set_field(index, field).unwrap();
+				}
+				data.set_row(row_id, writer.build()).unwrap();
+			}
+			fixture.page("Sheet", 0, language, &data);
+		}
+
+		fixture.build()
+	}
+
+	#[test]
+	fn rows_and_fields() {
+		let columns = [
+			ColumnDefinition::new(ColumnKind::String, 0),
+			ColumnDefinition::new(ColumnKind::UInt32, 4),
+		];
+		let old = ironworks(
+			&columns,
+			&[(1, "a", "x", 1), (2, "b", "y", 2), (3, "c", "z", 3)],
+		);
+		let new = ironworks(
+			&columns,
+			&[(1, "a", "x", 1), (2, "b", "w", 5), (4, "d", "v", 4)],
+		);
+
+		let diff = diff(&Excel::new(&old), &Excel::new(&new), "Sheet").unwrap();
+		assert!(diff.columns_added().is_empty());
+		assert!(diff.columns_removed().is_empty());
+		assert_eq!(diff.rows_added(), &[(4, 0)]);
+		assert_eq!(diff.rows_removed(), &[(3, 0)]);
+
+		let [row] = &diff.rows_changed()[..] else {
+			panic!("expected a single changed row")
+		};
+		assert_eq!(row.row_id(), 2);
+		let changes = row
+			.changes()
+			.iter()
+			.map(|change| (change.index(), change.language()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			changes,
+			vec![(0, Language::Japanese), (1, Language::Japanese)]
+		);
+	}
+
+	#[test]
+	fn columns() {
+		let old = ironworks(
+			&[
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::UInt32, 4),
+			],
+			&[(1, "a", "x", 1)],
+		);
+		let new = ironworks(
+			&[
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::Int32, 4),
+			],
+			&[(1, "a", "x", 1)],
+		);
+
+		let diff = diff(&Excel::new(&old), &Excel::new(&new), "Sheet").unwrap();
+		assert_eq!(
+			diff.columns_added(),
+			&[ColumnDefinition::new(ColumnKind::Int32, 4)]
+		);
+		assert_eq!(
+			diff.columns_removed(),
+			&[ColumnDefinition::new(ColumnKind::UInt32, 4)]
+		);
+		assert!(diff.rows_changed().is_empty());
+	}
+
+	#[test]
+	fn text() {
+		assert_eq!(
+			text_diff("the quick fox", "the slow fox"),
+			vec![
+				TextChange::Equal("the ".into()),
+				TextChange::Added("slow".into()),
+				TextChange::Removed("quick".into()),
+				TextChange::Equal(" fox".into()),
+			]
+		);
+		assert_eq!(
+			text_diff("same", "same"),
+			vec![TextChange::Equal("same".into())]
+		);
+	}
+}
@@ -9,8 +9,10 @@ use crate::{
 
 use super::{
 	borrowed::Borrowed,
+	cache::{CacheLimits, CacheStats, PageCache},
 	language::Language,
 	metadata::SheetMetadata,
+	overlay::Overlay,
 	path,
 	sheet::{Sheet, SheetCache},
 };
@@ -19,6 +21,10 @@ use super::{
 #[derive(Debug, Default)]
 pub struct ExcelOptions {
 	pub(super) language: Option<Language>,
+	pub(super) fallback: Option<Vec<Language>>,
+	pub(super) strict: bool,
+	pub(super) cache_limits: CacheLimits,
+	pub(super) overlay: Arc<Overlay>,
 }
 
 impl<'i> ExcelOptions {
@@ -28,6 +34,46 @@ impl<'i> ExcelOptions {
 		self
 	}
 
+	/// Set the languages to fall back to, in order, when a sheet does not
+	/// provide the requested language. Defaults to `[Language::None]`.
+	pub fn fallback(&mut self, languages: impl IntoIterator<Item = Language>) -> &mut Self {
+		self.fallback = Some(languages.into_iter().collect());
+		self
+	}
+
+	/// Set whether reads should fail if the requested language is not available,
+	/// rather than falling back. Defaults to `false`.
+	pub fn strict(&mut self, strict: bool) -> &mut Self {
+		self.strict = strict;
+		self
+	}
+
+	/// Set the maximum number of pages to hold in the page cache. When exceeded,
+	/// the least recently used pages are evicted. Defaults to unbounded.
+	///
+	/// Only page data is limited. Sheet headers and column indexes are retained
+	/// until removed with [`Excel::evict`] or [`Excel::clear_cache`].
+	pub fn cache_pages(&mut self, pages: usize) -> &mut Self {
+		self.cache_limits.pages = Some(pages);
+		self
+	}
+
+	/// Set the maximum size in bytes of page data to hold in the page cache. When
+	/// exceeded, the least recently used pages are evicted. Defaults to unbounded.
+	///
+	/// As with [`ExcelOptions::cache_pages`], only page data is limited; this
+	/// does not cap the total memory used by the database.
+	pub fn cache_bytes(&mut self, bytes: usize) -> &mut Self {
+		self.cache_limits.bytes = Some(bytes);
+		self
+	}
+
+	/// Set the in-memory rows and sheets to overlay on the database.
+	pub fn overlay(&mut self, overlay: Overlay) -> &mut Self {
+		self.overlay = overlay.into();
+		self
+	}
+
 	/// Build the configured Excel database.
 	pub fn build(&self, ironworks: impl Into<Borrowed<'i, Ironworks>>) -> Excel<'i> {
 		Excel::with_options(ironworks, self)
@@ -35,19 +81,30 @@ impl<'i> ExcelOptions {
 }
 
 /// An Excel database.
+///
+/// Clones of a database share its caches. Building a database from an
+/// `Arc<Ironworks>` results in an `Excel<'static>`, which may be freely stored
+/// and moved between threads.
+#[derive(Clone)]
 pub struct Excel<'i> {
 	default_language: Language,
+	default_fallback: Vec<Language>,
+	default_strict: bool,
 
 	ironworks: Borrowed<'i, Ironworks>,
 
-	list: OptionCache<file::exl::ExcelList>,
-	sheets: HashMapCache<String, SheetCache>,
+	list: Arc<OptionCache<file::exl::ExcelList>>,
+	sheets: Arc<HashMapCache<String, SheetCache>>,
+	pages: Arc<PageCache>,
+	overlay: Arc<Overlay>,
 }
 
 impl Debug for Excel<'_> {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("Excel")
 			.field("default_language", &self.default_language)
+			.field("default_fallback", &self.default_fallback)
+			.field("default_strict", &self.default_strict)
 			.finish()
 	}
 }
@@ -66,11 +123,18 @@ impl<'i> Excel<'i> {
 	fn with_options(ironworks: impl Into<Borrowed<'i, Ironworks>>, options: &ExcelOptions) -> Self {
 		Self {
 			default_language: options.language.unwrap_or(Language::None),
+			default_fallback: options
+				.fallback
+				.clone()
+				.unwrap_or_else(|| vec![Language::None]),
+			default_strict: options.strict,
 
 			ironworks: ironworks.into(),
 
 			list: Default::default(),
 			sheets: Default::default(),
+			pages: PageCache::new(options.cache_limits).into(),
+			overlay: options.overlay.clone(),
 		}
 	}
 
@@ -81,8 +145,13 @@ impl<'i> Excel<'i> {
 
 	/// Fetch the authoritative list of sheets in the database.
 	pub fn list(&self) -> Result<Arc<file::exl::ExcelList>> {
-		self.list
-			.try_get_or_insert(|| self.ironworks.file(path::exl()))
+		self.list.try_get_or_insert(|| {
+			let mut list = self.ironworks.file::<file::exl::ExcelList>(path::exl())?;
+			for sheet in self.overlay.sheet_names() {
+				list.insert(sheet, None);
+			}
+			Ok(list)
+		})
 	}
 
 	/// Fetch a sheet from the database.
@@ -104,8 +173,31 @@ impl<'i> Excel<'i> {
 		Ok(Sheet::new(
 			sheet_metadata,
 			self.default_language,
+			self.default_fallback.clone(),
+			self.default_strict,
 			self.ironworks.clone(),
 			cache,
+			self.pages.clone(),
+			self.overlay.clone(),
 		))
 	}
+
+	/// Clear all cached data held by the database. Sheets fetched prior to
+	/// clearing will retain their sheet metadata.
+	pub fn clear_cache(&self) {
+		*self.list.lock().unwrap() = None;
+		self.sheets.lock().unwrap().clear();
+		self.pages.clear();
+	}
+
+	/// Evict all cached data for the specified sheet.
+	pub fn evict(&self, sheet: &str) {
+		self.sheets.lock().unwrap().remove(sheet);
+		self.pages.evict(sheet);
+	}
+
+	/// Get statistics for the page cache of the database.
+	pub fn cache_stats(&self) -> CacheStats {
+		self.pages.stats()
+	}
 }
@@ -0,0 +1,432 @@
+//! Export of Excel sheets to textual formats.
+
+use std::io::Write;
+
+use crate::{
+	error::Result,
+	file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	sestring::SeString,
+};
+
+use super::{
+	field::Field,
+	language::Language,
+	metadata::SheetMetadata,
+	row::Row,
+	sheet::{RowConfig, Sheet},
+};
+
+/// Textual representation to use for SeString fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StringFormat {
+	/// Strings resolved with a default context, equivalent to their `Display`
+	/// implementation.
+	#[default]
+	Resolved,
+	/// Text content of strings, with all payloads omitted.
+	Text,
+	/// Lossless representation of strings, with payloads written as tags
+	/// containing their raw bytes, i.e. `<hex:02100103>`.
+	Tagged,
+}
+
+/// Options for exporting a sheet.
+#[derive(Debug, Default, Clone)]
+pub struct ExportOptions {
+	language: Option<Language>,
+	strings: StringFormat,
+}
+
+impl ExportOptions {
+	/// Set the language to export. If not set, the sheet's default language is used.
+	pub fn language(&mut self, language: Language) -> &mut Self {
+		self.language = Some(language);
+		self
+	}
+
+	/// Set the format used to render string fields.
+	pub fn strings(&mut self, format: StringFormat) -> &mut Self {
+		self.strings = format;
+		self
+	}
+
+	/// Write the sheet as CSV in the layout used by SaintCoinach's `rawexd`
+	/// output. The first three lines contain the column indices, offsets, and
+	/// types respectively, followed by one line per (sub)row.
+	pub fn csv<S>(&self, sheet: &Sheet<S>, mut writer: impl Write) -> Result<()>
+	where
+		S: SheetMetadata<Row = Row>,
+	{
+		let columns = sheet.columns()?;
+		write_csv_header(&mut writer, &columns)?;
+
+		let subrows = sheet.kind()? == SheetKind::Subrows;
+		for row in sheet.try_iter_with_options(self.config()) {
+			self.write_csv_row(&mut writer, &row?, &columns, subrows)?;
+		}
+
+		writer.flush()?;
+		Ok(())
+	}
+
+	/// Write the sheet as JSON lines, with one `{"key": ..., "fields": [...]}`
+	/// object per (sub)row.
+	pub fn json_lines<S>(&self, sheet: &Sheet<S>, mut writer: impl Write) -> Result<()>
+	where
+		S: SheetMetadata<Row = Row>,
+	{
+		let columns = sheet.columns()?;
+		let subrows = sheet.kind()? == SheetKind::Subrows;
+		for row in sheet.try_iter_with_options(self.config()) {
+			self.write_json_row(&mut writer, &row?, &columns, subrows)?;
+		}
+
+		writer.flush()?;
+		Ok(())
+	}
+
+	fn config(&self) -> RowConfig {
+		RowConfig {
+			language: self.language,
+			..Default::default()
+		}
+	}
+
+	fn write_csv_row(
+		&self,
+		writer: &mut impl Write,
+		row: &Row,
+		columns: &[ColumnDefinition],
+		subrows: bool,
+	) -> Result<()> {
+		let mut record = vec![key(row, subrows)];
+		for column in columns {
+			let value = match row.field(column)? {
+				// Strings are always quoted, matching SaintCoinach.
+				Field::String(string) => {
+					format!("\"{}\"", self.string(&string)?.replace('"', "\"\""))
+				}
+				Field::Bool(true) => "True".into(),
+				Field::Bool(false) => "False".into(),
+				other => number_string(other),
+			};
+			record.push(value);
+		}
+
+		writeln!(writer, "{}", record.join(","))?;
+		Ok(())
+	}
+
+	fn write_json_row(
+		&self,
+		writer: &mut impl Write,
+		row: &Row,
+		columns: &[ColumnDefinition],
+		subrows: bool,
+	) -> Result<()> {
+		let mut fields = Vec::with_capacity(columns.len());
+		for column in columns {
+			let value = match row.field(column)? {
+				Field::String(string) => json_string(&self.string(&string)?),
+				Field::Bool(value) => value.to_string(),
+				Field::F32(value) if !value.is_finite() => "null".into(),
+				other => number_string(other),
+			};
+			fields.push(value);
+		}
+
+		writeln!(
+			writer,
+			r#"{{"key":{},"fields":[{}]}}"#,
+			json_string(&key(row, subrows)),
+			fields.join(",")
+		)?;
+		Ok(())
+	}
+
+	fn string(&self, string: &SeString) -> Result<String> {
+		let value = match self.strings {
+			StringFormat::Resolved => string.to_string(),
+			StringFormat::Text => string.text()?,
+			StringFormat::Tagged => string.tagged()?,
+		};
+		Ok(value)
+	}
+}
+
+/// Create an export options builder.
+pub fn with() -> ExportOptions {
+	ExportOptions::default()
+}
+
+/// Write the sheet as CSV in SaintCoinach's `rawexd` layout using default
+/// options. See [`ExportOptions::csv`].
+pub fn csv<S>(sheet: &Sheet<S>, writer: impl Write) -> Result<()>
+where
+	S: SheetMetadata<Row = Row>,
+{
+	with().csv(sheet, writer)
+}
+
+/// Write the sheet as JSON lines using default options. See
+/// [`ExportOptions::json_lines`].
+pub fn json_lines<S>(sheet: &Sheet<S>, writer: impl Write) -> Result<()>
+where
+	S: SheetMetadata<Row = Row>,
+{
+	with().json_lines(sheet, writer)
+}
+
+fn write_csv_header(writer: &mut impl Write, columns: &[ColumnDefinition]) -> Result<()> {
+	let line = |first: &str, values: Vec<String>| {
+		std::iter::once(first.to_string())
+			.chain(values)
+			.collect::<Vec<_>>()
+			.join(",")
+	};
+
+	let indices = (0..columns.len()).map(|index| index.to_string()).collect();
+	let offsets = columns
+		.iter()
+		.map(|column| column.offset().to_string())
+		.collect();
+	let kinds = columns
+		.iter()
+		.map(|column| kind_name(column.kind()))
+		.collect();
+
+	writeln!(writer, "{}", line("key", indices))?;
+	writeln!(writer, "{}", line("#", offsets))?;
+	writeln!(writer, "{}", line("int32", kinds))?;
+	Ok(())
+}
+
+// Type names as used by SaintCoinach.
+fn kind_name(kind: ColumnKind) -> String {
+	use ColumnKind as K;
+	let name = match kind {
+		K::String => "str",
+		K::Bool => "bool",
+		K::Int8 => "sbyte",
+		K::UInt8 => "byte",
+		K::Int16 => "int16",
+		K::UInt16 => "uint16",
+		K::Int32 => "int32",
+		K::UInt32 => "uint32",
+		K::Float32 => "single",
+		K::Int64 => "int64",
+		K::UInt64 => "uint64",
+		packed => {
+			let bit = u16::from(packed) - u16::from(K::PackedBool0);
+			return format!("bit&{:02X}", 1 << bit);
+		}
+	};
+	name.into()
+}
+
+fn key(row: &Row, subrows: bool) -> String {
+	match subrows {
+		true => format!("{}.{}", row.row_id(), row.subrow_id()),
+		false => row.row_id().to_string(),
+	}
+}
+
+fn number_string(field: Field) -> String {
+	match field {
+		Field::I8(value) => value.to_string(),
+		Field::I16(value) => value.to_string(),
+		Field::I32(value) => value.to_string(),
+		Field::I64(value) => value.to_string(),
+		Field::U8(value) => value.to_string(),
+		Field::U16(value) => value.to_string(),
+		Field::U32(value) => value.to_string(),
+		Field::U64(value) => value.to_string(),
+		Field::F32(value) => value.to_string(),
+		Field::String(_) | Field::Bool(_) => unreachable!("non-numeric field {field:?}"),
+	}
+}
+
+fn json_string(value: &str) -> String {
+	let mut output = String::with_capacity(value.len() + 2);
+	output.push('"');
+	for character in value.chars() {
+		match character {
+			'"' => output.push_str("\\\""),
+			'\\' => output.push_str("\\\\"),
+			'\n' => output.push_str("\\n"),
+			'\r' => output.push_str("\\r"),
+			'\t' => output.push_str("\\t"),
+			control if control.is_control() => {
+				output.push_str(&format!("\\u{:04x}", u32::from(control)))
+			}
+			other => output.push(other),
+		}
+	}
+	output.push('"');
+	output
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		excel::{fixture::Fixture, path, row::Row, Excel, Field, Language, RowWriter},
+		file::exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		sestring::SeString,
+	};
+
+	use super::{write_csv_header, ExportOptions, StringFormat};
+
+	fn header() -> Arc<ExcelHeader> {
+		Arc::new(ExcelHeader::new(
+			SheetKind::Default,
+			12,
+			vec![
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::Float32, 4),
+				ColumnDefinition::new(ColumnKind::Int16, 8),
+				ColumnDefinition::new(ColumnKind::PackedBool3, 10),
+			],
+			vec![PageDefinition::new(0, 1)],
+			1,
+			vec![1],
+		))
+	}
+
+	fn row(string: &[u8]) -> Row {
+		let mut writer = RowWriter::new(header());
+		writer
+			.set_field(0, Field::String(test_string(string)))
+			.unwrap()
+			.set_field(1, Field::F32(0.5))
+			.unwrap()
+			.set_field(2, Field::I16(-3))
+			.unwrap()
+			.set_field(3, Field::Bool(true))
+			.unwrap();
+		Row::new(7, 2, Language::None, header(), writer.build())
+	}
+
+	fn test_string(bytes: &[u8]) -> SeString {
+		use binrw::BinReaderExt;
+		std::io::Cursor::new(bytes).read_be::<SeString>().unwrap()
+	}
+
+	#[test]
+	fn csv_header() {
+		let mut output = Vec::new();
+		write_csv_header(&mut output, header().columns()).unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"key,0,1,2,3\n#,0,4,8,10\nint32,str,single,int16,bit&08\n"
+		);
+	}
+
+	#[test]
+	fn csv_row() {
+		let mut output = Vec::new();
+		ExportOptions::default()
+			.write_csv_row(&mut output, &row(b"say \"hi\""), header().columns(), true)
+			.unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"7.2,\"say \"\"hi\"\"\",0.5,-3,True\n"
+		);
+	}
+
+	#[test]
+	fn json_row() {
+		let mut output = Vec::new();
+		ExportOptions::default()
+			.write_json_row(&mut output, &row(b"a\nb"), header().columns(), false)
+			.unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"{\"key\":\"7\",\"fields\":[\"a\\nb\",0.5,-3,true]}\n"
+		);
+	}
+
+	#[test]
+	fn string_formats() {
+		// Soft hyphen payload between two text segments.
+		let bytes = b"a\x02\x16\x01\x03b";
+		let row = row(bytes);
+		let render = |format| {
+			let mut output = Vec::new();
+			ExportOptions::default()
+				.strings(format)
+				.write_csv_row(&mut output, &row, &header().columns()[..1], false)
+				.unwrap();
+			String::from_utf8(output).unwrap()
+		};
+
+		assert_eq!(render(StringFormat::Text), "7,\"ab\"\n");
+		assert_eq!(render(StringFormat::Tagged), "7,\"a<hex:02160103>b\"\n");
+	}
+
+	// A subrow sheet spanning two pages.
+	fn fixture() -> Fixture {
+		let mut fixture = Fixture::new();
+		fixture.sheet(
+			"Sheet",
+			SheetKind::Subrows,
+			&[Language::English],
+			&[(0, &[(0, &[1, 2])]), (1, &[(1, &[3])])],
+		);
+		fixture
+	}
+
+	fn options() -> ExportOptions {
+		let mut options = ExportOptions::default();
+		options.language(Language::English);
+		options
+	}
+
+	#[test]
+	fn csv_sheet() {
+		let ironworks = fixture().build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		let mut output = Vec::new();
+		options().csv(&sheet, &mut output).unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"key,0\n#,0\nint32,uint32\n0.0,1\n0.1,2\n1.0,3\n"
+		);
+	}
+
+	#[test]
+	fn json_lines_sheet() {
+		let ironworks = fixture().build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		let mut output = Vec::new();
+		options().json_lines(&sheet, &mut output).unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			concat!(
+				"{\"key\":\"0.0\",\"fields\":[1]}\n",
+				"{\"key\":\"0.1\",\"fields\":[2]}\n",
+				"{\"key\":\"1.0\",\"fields\":[3]}\n",
+			)
+		);
+	}
+
+	#[test]
+	fn unreadable_page() {
+		let ironworks = fixture()
+			.file(
+				&path::exd("Sheet", 1, Language::English),
+				b"invalid".to_vec(),
+			)
+			.build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		options().csv(&sheet, &mut Vec::new()).unwrap_err();
+		options().json_lines(&sheet, &mut Vec::new()).unwrap_err();
+	}
+}
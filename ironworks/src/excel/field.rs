@@ -1,10 +1,18 @@
 use enum_as_inner::EnumAsInner;
 
-use crate::sestring::SeString;
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sestring::{Context, SeString},
+};
 
 /// A single field from an Excel database.
+///
+/// Fields can be converted to primitive types with `TryFrom`. Conversions only
+/// succeed where every value of the field's type can be represented by the
+/// target type, i.e. a `U8` field can be read as a `u16` or `i32`, but an `I8`
+/// field cannot be read as a `u16`.
 #[allow(missing_docs)]
-#[derive(Debug, EnumAsInner)]
+#[derive(Debug, Clone, EnumAsInner)]
 pub enum Field {
 	String(SeString),
 
@@ -22,3 +30,240 @@ pub enum Field {
 
 	F32(f32),
 }
+
+impl Field {
+	/// Read the value of an integer field as a `u64`. Returns `None` if the field
+	/// is not an integer, or is a negative signed integer.
+	pub fn as_u64_lossless(&self) -> Option<u64> {
+		use Field as F;
+		match *self {
+			F::U8(value) => Some(value.into()),
+			F::U16(value) => Some(value.into()),
+			F::U32(value) => Some(value.into()),
+			F::U64(value) => Some(value),
+			F::I8(value) => value.try_into().ok(),
+			F::I16(value) => value.try_into().ok(),
+			F::I32(value) => value.try_into().ok(),
+			F::I64(value) => value.try_into().ok(),
+			_ => None,
+		}
+	}
+
+	/// Read the value of a numeric field as an `f64`. 64-bit integers beyond the
+	/// precision of an `f64` are rounded. Returns `None` if the field is not
+	/// numeric.
+	pub fn as_f64(&self) -> Option<f64> {
+		use Field as F;
+		match *self {
+			F::I8(value) => Some(value.into()),
+			F::I16(value) => Some(value.into()),
+			F::I32(value) => Some(value.into()),
+			F::I64(value) => Some(value as f64),
+			F::U8(value) => Some(value.into()),
+			F::U16(value) => Some(value.into()),
+			F::U32(value) => Some(value.into()),
+			F::U64(value) => Some(value as f64),
+			F::F32(value) => Some(value.into()),
+			_ => None,
+		}
+	}
+
+	/// Convert a string field into its text, resolved with a default context.
+	pub fn into_string_resolved(self) -> Result<String> {
+		try_from_field(self, "String")
+	}
+
+	// Name of the kind of value held by this field.
+	fn kind_name(&self) -> &'static str {
+		use Field as F;
+		match self {
+			F::String(_) => "String",
+			F::Bool(_) => "Bool",
+			F::I8(_) => "I8",
+			F::I16(_) => "I16",
+			F::I32(_) => "I32",
+			F::I64(_) => "I64",
+			F::U8(_) => "U8",
+			F::U16(_) => "U16",
+			F::U32(_) => "U32",
+			F::U64(_) => "U64",
+			F::F32(_) => "F32",
+		}
+	}
+}
+
+/// Types that fields can be read as with [`Row::get`](super::Row::get). See
+/// [`Field`] for the supported conversions.
+pub trait FromField: Sized {
+	#[doc(hidden)]
+	fn from_field(field: Field) -> Result<Self, ConversionError>;
+}
+
+// Reason a field could not be converted to a value.
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum ConversionError {
+	// The kind of the field cannot be represented by the target type.
+	Kind,
+	// The value of the field failed to convert.
+	Value(Error),
+}
+
+macro_rules! from_field {
+	($type:ty: $($variant:ident),+) => {
+		impl FromField for $type {
+			fn from_field(field: Field) -> Result<Self, ConversionError> {
+				match field {
+					$(Field::$variant(value) => Ok(value.into()),)+
+					_ => Err(ConversionError::Kind),
+				}
+			}
+		}
+
+		impl TryFrom<Field> for $type {
+			type Error = Error;
+
+			fn try_from(field: Field) -> Result<Self> {
+				try_from_field(field, stringify!($type))
+			}
+		}
+	};
+}
+
+from_field!(bool: Bool);
+from_field!(SeString: String);
+
+from_field!(i8: I8);
+from_field!(i16: I16, I8, U8);
+from_field!(i32: I32, I16, I8, U16, U8);
+from_field!(i64: I64, I32, I16, I8, U32, U16, U8);
+
+from_field!(u8: U8);
+from_field!(u16: U16, U8);
+from_field!(u32: U32, U16, U8);
+from_field!(u64: U64, U32, U16, U8);
+
+from_field!(f32: F32, I16, I8, U16, U8);
+from_field!(f64: F32, I32, I16, I8, U32, U16, U8);
+
+impl FromField for String {
+	fn from_field(field: Field) -> Result<Self, ConversionError> {
+		match field {
+			Field::String(value) => value
+				.resolve(&mut Context::default())
+				.map_err(ConversionError::Value),
+			_ => Err(ConversionError::Kind),
+		}
+	}
+}
+
+/// Converts string fields to their resolved text. See
+/// [`Field::into_string_resolved`].
+impl TryFrom<Field> for String {
+	type Error = Error;
+
+	fn try_from(field: Field) -> Result<Self> {
+		field.into_string_resolved()
+	}
+}
+
+fn try_from_field<T: FromField>(field: Field, target: &str) -> Result<T> {
+	let kind = field.kind_name();
+	T::from_field(field).map_err(|error| match error {
+		ConversionError::Kind => Error::Invalid(
+			ErrorValue::Other("Excel field".into()),
+			format!("cannot convert {kind} field to {target}"),
+		),
+		ConversionError::Value(error) => error,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		error::{Error, ErrorValue},
+		excel::{row::Row, Language},
+		file::exh::{ColumnDefinition, ColumnKind, ExcelHeader, SheetKind},
+		sestring::SeString,
+	};
+
+	use super::Field;
+
+	#[test]
+	fn widening() {
+		assert_eq!(u64::try_from(Field::U8(5)).unwrap(), 5);
+		assert_eq!(i32::try_from(Field::U16(5)).unwrap(), 5);
+		assert_eq!(f64::try_from(Field::I32(-5)).unwrap(), -5.0);
+		assert!(u16::try_from(Field::I8(5)).is_err());
+		assert!(u8::try_from(Field::U16(5)).is_err());
+		assert!(i64::try_from(Field::U64(5)).is_err());
+		assert!(f32::try_from(Field::U32(5)).is_err());
+	}
+
+	#[test]
+	fn coercion() {
+		assert_eq!(Field::I16(7).as_u64_lossless(), Some(7));
+		assert_eq!(Field::I16(-7).as_u64_lossless(), None);
+		assert_eq!(Field::F32(7.0).as_u64_lossless(), None);
+		assert_eq!(Field::U64(7).as_f64(), Some(7.0));
+		assert_eq!(Field::Bool(true).as_f64(), None);
+	}
+
+	#[test]
+	fn strings() {
+		let field = Field::String(SeString::try_from("text").unwrap());
+		assert_eq!(String::try_from(field.clone()).unwrap(), "text");
+		assert_eq!(field.into_string_resolved().unwrap(), "text");
+		assert!(Field::U8(1).into_string_resolved().is_err());
+	}
+
+	#[test]
+	fn row_get() {
+		let header = ExcelHeader::new(
+			SheetKind::Default,
+			4,
+			vec![
+				ColumnDefinition::new(ColumnKind::Int16, 0),
+				ColumnDefinition::new(ColumnKind::UInt8, 2),
+			],
+			vec![],
+			0,
+			vec![],
+		);
+		let row = Row::new(0, 0, Language::None, Arc::new(header), vec![255, 253, 7, 0]);
+
+		assert_eq!(row.get::<i64>(0).unwrap(), -3);
+		assert_eq!(row.get::<u32>(1).unwrap(), 7);
+
+		let error = row.get::<u16>(0).unwrap_err().to_string();
+		assert!(error.contains("Int16 column at offset 0 as u16"), "{error}");
+	}
+
+	#[test]
+	fn row_get_resolution_error() {
+		let header = ExcelHeader::new(
+			SheetKind::Default,
+			4,
+			vec![ColumnDefinition::new(ColumnKind::String, 0)],
+			vec![],
+			0,
+			vec![],
+		);
+		// String data with invalid UTF-8 text, which fails when resolved.
+		let row = Row::new(
+			0,
+			0,
+			Language::None,
+			Arc::new(header),
+			vec![0, 0, 0, 0, 0xff, 0],
+		);
+
+		let error = row.get::<String>(0).unwrap_err();
+		assert!(
+			matches!(error, Error::Invalid(ErrorValue::SeString, _)),
+			"{error}"
+		);
+	}
+}
@@ -0,0 +1,132 @@
+// In-memory Excel databases for use in tests.
+
+use std::{collections::HashMap, io::Cursor, sync::Arc};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::{
+		exd::ExcelData,
+		exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		exl::ExcelList,
+		WriteFile,
+	},
+	FileStream, Ironworks, Resource,
+};
+
+use super::{language::Language, path};
+
+// Rows of a page, as pairs of row ID and subrow values.
+pub type Rows<'a> = &'a [(u32, &'a [u32])];
+
+#[derive(Debug, Default)]
+pub struct Fixture {
+	files: HashMap<String, Vec<u8>>,
+	list: ExcelList,
+}
+
+impl Fixture {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// Add a sheet with a single u32 column. Each page is a list of rows, with each
+	// row being a list of subrow values. Sheets without subrows should provide
+	// exactly one value per row.
+	pub fn sheet(
+		&mut self,
+		name: &str,
+		kind: SheetKind,
+		languages: &[Language],
+		pages: &[(u32, Rows)],
+	) -> &mut Self {
+		self.header(
+			name,
+			&ExcelHeader::new(
+				kind,
+				4,
+				vec![ColumnDefinition::new(ColumnKind::UInt32, 0)],
+				pages
+					.iter()
+					.map(|&(start_id, rows)| {
+						let end_id = rows.last().map(|&(id, _)| id + 1).unwrap_or(start_id);
+						PageDefinition::new(start_id, end_id - start_id)
+					})
+					.collect(),
+				pages
+					.iter()
+					.map(|&(_, rows)| u32::try_from(rows.len()).unwrap())
+					.sum(),
+				languages.iter().map(|&language| language.into()).collect(),
+			),
+		);
+
+		for &language in languages {
+			for &(start_id, rows) in pages {
+				let mut data = ExcelData::new();
+				for &(row_id, values) in rows {
+					for (subrow_id, value) in values.iter().enumerate() {
+						let bytes = value.to_be_bytes().to_vec();
+						match kind {
+							SheetKind::Subrows => data
+								.set_subrow(row_id, subrow_id.try_into().unwrap(), bytes)
+								.unwrap(),
+							_ => data.set_row(row_id, bytes).unwrap(),
+						}
+					}
+				}
+				self.page(name, start_id, language, &data);
+			}
+		}
+
+		self
+	}
+
+	// Add the header for a sheet, listing it in the root list.
+	pub fn header(&mut self, name: &str, header: &ExcelHeader) -> &mut Self {
+		self.list.insert(name, None);
+		self.file(&path::exh(name), write(header))
+	}
+
+	pub fn page(
+		&mut self,
+		name: &str,
+		start_id: u32,
+		language: Language,
+		data: &ExcelData,
+	) -> &mut Self {
+		self.file(&path::exd(name, start_id, language), write(data))
+	}
+
+	pub fn file(&mut self, path: &str, data: Vec<u8>) -> &mut Self {
+		self.files.insert(path.to_string(), data);
+		self
+	}
+
+	pub fn build(&mut self) -> Ironworks {
+		let mut files = self.files.clone();
+		files.insert(path::exl().to_string(), write(&self.list));
+		Ironworks::new().with_resource(MemoryResource(Arc::new(files)))
+	}
+}
+
+fn write(file: &impl WriteFile) -> Vec<u8> {
+	let mut cursor = Cursor::new(Vec::new());
+	file.write(&mut cursor).unwrap();
+	cursor.into_inner()
+}
+
+struct MemoryResource(Arc<HashMap<String, Vec<u8>>>);
+
+impl Resource for MemoryResource {
+	fn version(&self, _path: &str) -> Result<String> {
+		Ok("fixture".into())
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let data = self
+			.0
+			.get(path)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+		Ok(Box::new(Cursor::new(data.clone())))
+	}
+}
@@ -1,21 +1,42 @@
 //! Tools for working with the Excel database format.
 
+pub mod export;
+#[cfg(feature = "search")]
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serialize;
+
 mod borrowed;
+mod cache;
+mod diff;
 mod excel;
 mod field;
+#[cfg(test)]
+mod fixture;
 mod language;
 mod metadata;
+mod overlay;
 mod path;
 mod row;
 mod sheet;
+mod signature;
+mod writer;
 
 pub use {
+	cache::CacheStats,
+	diff::{diff, FieldChange, RowDiff, SheetDiff, TextChange},
 	excel::{Excel, ExcelOptions},
-	field::Field,
+	field::{Field, FromField},
 	language::Language,
 	metadata::SheetMetadata,
+	overlay::{Overlay, OverlayRow},
 	row::{ColumnSpecifier, Row},
-	sheet::{RowOptions, Sheet, SheetIterator},
+	sheet::{
+		ColumnIndex, GroupedIterator, LanguagesIterator, PageSummary, Predicate, Query, RowOptions,
+		Sheet, SheetIterator, TrySheetIterator,
+	},
+	signature::{ColumnMapping, ColumnSignature},
+	writer::RowWriter,
 };
 
 #[cfg(test)]
@@ -25,28 +46,60 @@ mod test {
 	#[test]
 	fn test_send() {
 		fn assert_send<T: Send>() {}
+		assert_send::<CacheStats>();
+		assert_send::<ColumnIndex>();
+		assert_send::<ColumnMapping>();
+		assert_send::<ColumnSignature>();
 		assert_send::<ColumnSpecifier>();
 		assert_send::<Excel>();
 		assert_send::<ExcelOptions>();
 		assert_send::<Field>();
+		assert_send::<FieldChange>();
 		assert_send::<Language>();
+		assert_send::<GroupedIterator<()>>();
+		assert_send::<LanguagesIterator<()>>();
+		assert_send::<Overlay>();
+		assert_send::<OverlayRow>();
+		assert_send::<PageSummary>();
 		assert_send::<Row>();
+		assert_send::<Predicate>();
+		assert_send::<Query<()>>();
+		assert_send::<RowDiff>();
 		assert_send::<RowOptions<()>>();
 		assert_send::<Sheet<()>>();
+		assert_send::<SheetDiff>();
 		assert_send::<SheetIterator<()>>();
+		assert_send::<TrySheetIterator<()>>();
+		assert_send::<RowWriter>();
 	}
 
 	#[test]
 	fn test_sync() {
 		fn assert_sync<T: Sync>() {}
+		assert_sync::<CacheStats>();
+		assert_sync::<ColumnIndex>();
+		assert_sync::<ColumnMapping>();
+		assert_sync::<ColumnSignature>();
 		assert_sync::<ColumnSpecifier>();
 		assert_sync::<Excel>();
 		assert_sync::<ExcelOptions>();
 		assert_sync::<Field>();
+		assert_sync::<FieldChange>();
 		assert_sync::<Language>();
+		assert_sync::<GroupedIterator<()>>();
+		assert_sync::<LanguagesIterator<()>>();
+		assert_sync::<Overlay>();
+		assert_sync::<OverlayRow>();
+		assert_sync::<PageSummary>();
 		assert_sync::<Row>();
+		assert_sync::<Predicate>();
+		assert_sync::<Query<()>>();
+		assert_sync::<RowDiff>();
 		assert_sync::<RowOptions<()>>();
 		assert_sync::<Sheet<()>>();
+		assert_sync::<SheetDiff>();
 		assert_sync::<SheetIterator<()>>();
+		assert_sync::<TrySheetIterator<()>>();
+		assert_sync::<RowWriter>();
 	}
 }
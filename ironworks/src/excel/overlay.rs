@@ -0,0 +1,558 @@
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	sync::Arc,
+};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::{exd, exh},
+};
+
+use super::{field::Field, language::Language, path, writer::RowWriter};
+
+/// A single (sub)row of fields to overlay on a sheet.
+///
+/// With the `serde` feature enabled, rows may be deserialized from the format
+/// produced when serializing a [`Row`](super::Row), with the subrow ID being
+/// optional. Fields are read as plain values, and converted to the kind of
+/// their column when the row is read.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct OverlayRow {
+	/// ID of the row.
+	pub row_id: u32,
+	/// ID of the subrow. Sheets without subrows only support subrow 0.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub subrow_id: u16,
+	/// Values of the row's fields, in column order. Columns without a value
+	/// default to zero or empty values.
+	pub fields: Vec<Field>,
+}
+
+impl OverlayRow {
+	/// Create a row with the specified ID and fields.
+	pub fn new(row_id: u32, fields: Vec<Field>) -> Self {
+		Self {
+			row_id,
+			subrow_id: 0,
+			fields,
+		}
+	}
+
+	/// Create a subrow with the specified IDs and fields.
+	pub fn subrow(row_id: u32, subrow_id: u16, fields: Vec<Field>) -> Self {
+		Self {
+			row_id,
+			subrow_id,
+			fields,
+		}
+	}
+}
+
+/// In-memory rows and sheets layered over the data of an Excel database.
+///
+/// Overlaid rows are read in place of any row with the same ID in the
+/// underlying data, and are laid out following the columns of the sheet's
+/// header, such that they can be read like any other row. Overlaid sheets
+/// replace the underlying sheet entirely. Rows that cannot be laid out following
+/// the header are left out of the sheet's pages, and fail only reads of the
+/// (sub)row itself.
+#[derive(Debug, Default)]
+pub struct Overlay {
+	sheets: HashMap<String, SheetOverlay>,
+}
+
+#[derive(Debug, Default)]
+struct SheetOverlay {
+	header: Option<exh::ExcelHeader>,
+	rows: HashMap<Language, BTreeMap<(u32, u16), Vec<Field>>>,
+}
+
+impl Overlay {
+	/// Create a new, empty overlay.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Overlay an entire sheet, described by `header`. No data is read from the
+	/// underlying database for the sheet; rows should be added with
+	/// [`Overlay::row`]. The page definitions of the header are ignored.
+	pub fn sheet(&mut self, sheet: impl Into<String>, header: exh::ExcelHeader) -> &mut Self {
+		self.sheets.entry(sheet.into()).or_default().header = Some(header);
+		self
+	}
+
+	/// Overlay a (sub)row in the specified language of a sheet, replacing any
+	/// existing (sub)row with the same IDs.
+	pub fn row(
+		&mut self,
+		sheet: impl Into<String>,
+		language: Language,
+		row: OverlayRow,
+	) -> &mut Self {
+		self.rows(sheet, language, [row])
+	}
+
+	/// Overlay multiple (sub)rows in the specified language of a sheet. See
+	/// [`Overlay::row`].
+	pub fn rows(
+		&mut self,
+		sheet: impl Into<String>,
+		language: Language,
+		rows: impl IntoIterator<Item = OverlayRow>,
+	) -> &mut Self {
+		let sheet_rows = self
+			.sheets
+			.entry(sheet.into())
+			.or_default()
+			.rows
+			.entry(language)
+			.or_default();
+		for row in rows {
+			sheet_rows.insert((row.row_id, row.subrow_id), row.fields);
+		}
+		self
+	}
+
+	// Names of sheets overlaid in their entirety.
+	pub(super) fn sheet_names(&self) -> impl Iterator<Item = &str> {
+		self.sheets
+			.iter()
+			.filter(|(_, overlay)| overlay.header.is_some())
+			.map(|(name, _)| name.as_str())
+	}
+
+	// Build the header of a sheet, with pages added to cover any overlaid rows
+	// not already covered by the pages of the underlying header.
+	pub(super) fn header(
+		&self,
+		sheet: &str,
+		base: impl FnOnce() -> Result<exh::ExcelHeader>,
+	) -> Result<exh::ExcelHeader> {
+		let Some(overlay) = self.sheets.get(sheet) else {
+			return base();
+		};
+
+		let header = match &overlay.header {
+			Some(header) => overlay.extend_header(header, vec![], 0),
+			None => {
+				let header = base()?;
+				overlay.extend_header(&header, header.pages().clone(), header.row_count())
+			}
+		};
+
+		Ok(header)
+	}
+
+	// Build a page of a sheet, with any overlaid rows within the page applied
+	// over the underlying data.
+	pub(super) fn page(
+		&self,
+		sheet: &str,
+		header: &Arc<exh::ExcelHeader>,
+		start_id: u32,
+		language: Language,
+		base: impl FnOnce() -> Result<exd::ExcelData>,
+	) -> Result<exd::ExcelData> {
+		let Some(overlay) = self.sheets.get(sheet) else {
+			return base();
+		};
+
+		let end_id = header
+			.pages()
+			.iter()
+			.find(|page| page.start_id() == start_id)
+			.map_or(start_id, |page| start_id + page.row_count());
+		let rows = overlay
+			.rows
+			.get(&language)
+			.map(|rows| rows.range((start_id, 0)..(end_id, 0)))
+			.into_iter()
+			.flatten()
+			.collect::<Vec<_>>();
+
+		let mut page = match (&overlay.header, rows.is_empty()) {
+			(Some(_), true) => {
+				return Err(Error::NotFound(ErrorValue::Path(path::exd(
+					sheet, start_id, language,
+				))))
+			}
+			(Some(_), false) => exd::ExcelData::new(),
+			(None, _) => match base() {
+				Err(Error::NotFound(ErrorValue::Path(_))) if !rows.is_empty() => {
+					exd::ExcelData::new()
+				}
+				other => other?,
+			},
+		};
+
+		for (&(row_id, subrow_id), fields) in rows {
+			// Invalid rows are reported when read, see Overlay::check_row.
+			let Ok(data) = build_row(sheet, header, row_id, subrow_id, fields) else {
+				continue;
+			};
+			match header.kind() {
+				exh::SheetKind::Subrows => page.set_subrow(row_id, subrow_id, data)?,
+				_ => page.set_row(row_id, data)?,
+			}
+		}
+
+		Ok(page)
+	}
+
+	// Check that the overlaid (sub)row with the specified IDs, if any, can be laid
+	// out following the header of the sheet.
+	pub(super) fn check_row(
+		&self,
+		sheet: &str,
+		header: &Arc<exh::ExcelHeader>,
+		row_id: u32,
+		subrow_id: u16,
+		language: Language,
+	) -> Result<()> {
+		let fields = self
+			.sheets
+			.get(sheet)
+			.and_then(|overlay| overlay.rows.get(&language))
+			.and_then(|rows| rows.get(&(row_id, subrow_id)));
+
+		match fields {
+			Some(fields) => build_row(sheet, header, row_id, subrow_id, fields).map(|_| ()),
+			None => Ok(()),
+		}
+	}
+}
+
+impl SheetOverlay {
+	fn extend_header(
+		&self,
+		source: &exh::ExcelHeader,
+		mut pages: Vec<exh::PageDefinition>,
+		row_count: u32,
+	) -> exh::ExcelHeader {
+		let covered = |row_id: u32, pages: &[exh::PageDefinition]| {
+			pages.iter().any(|page| {
+				page.start_id() <= row_id && page.start_id() + page.row_count() > row_id
+			})
+		};
+
+		// Group uncovered rows into runs of consecutive IDs, each forming a page.
+		let uncovered = self
+			.rows
+			.values()
+			.flat_map(|rows| rows.keys().map(|&(row_id, _)| row_id))
+			.filter(|&row_id| !covered(row_id, &pages))
+			.collect::<BTreeSet<_>>();
+
+		// Overlaid rows within the underlying pages are assumed to replace existing
+		// rows, only uncovered rows add to the row count.
+		let row_count = row_count + u32::try_from(uncovered.len()).unwrap();
+
+		let mut runs = Vec::<(u32, u32)>::new();
+		for row_id in uncovered {
+			match runs.last_mut() {
+				Some((start_id, count)) if *start_id + *count == row_id => *count += 1,
+				_ => runs.push((row_id, 1)),
+			}
+		}
+		pages.extend(
+			runs.into_iter()
+				.map(|(start_id, count)| exh::PageDefinition::new(start_id, count)),
+		);
+		pages.sort_by_key(|page| page.start_id());
+
+		let mut languages = source.languages().clone();
+		languages.extend(self.rows.keys().map(|&language| u8::from(language)));
+		let mut languages = languages.into_iter().collect::<Vec<_>>();
+		languages.sort_unstable();
+
+		exh::ExcelHeader::new(
+			source.kind(),
+			source.row_size(),
+			source.columns().clone(),
+			pages,
+			row_count,
+			languages,
+		)
+	}
+}
+
+// Build the binary representation of an overlaid (sub)row.
+fn build_row(
+	sheet: &str,
+	header: &Arc<exh::ExcelHeader>,
+	row_id: u32,
+	subrow_id: u16,
+	fields: &[Field],
+) -> Result<Vec<u8>> {
+	let error = |message: String| {
+		Error::Invalid(
+			ErrorValue::Row {
+				row: row_id,
+				subrow: subrow_id,
+				sheet: Some(sheet.into()),
+			},
+			message,
+		)
+	};
+
+	if subrow_id != 0 && header.kind() != exh::SheetKind::Subrows {
+		return Err(error(
+			"subrows are not supported by sheets without subrows".into(),
+		));
+	}
+
+	let columns = header.columns();
+	if fields.len() > columns.len() {
+		return Err(error(format!(
+			"{} fields provided for {} columns",
+			fields.len(),
+			columns.len()
+		)));
+	}
+
+	let mut writer = RowWriter::new(header.clone());
+	for (index, (field, column)) in fields.iter().zip(columns).enumerate() {
+		let field = coerce(field, column.kind()).ok_or_else(|| {
+			error(format!(
+				"cannot convert {field:?} for {:?} column {index}",
+				column.kind()
+			))
+		})?;
+		writer.set_field(index, field)?;
+	}
+
+	Ok(writer.build())
+}
+
+// Convert a field to the representation used by the specified column kind,
+// failing if the value cannot be represented.
+fn coerce(field: &Field, kind: exh::ColumnKind) -> Option<Field> {
+	use exh::ColumnKind as K;
+	use Field as F;
+
+	let field = match (kind, field) {
+		(K::String, F::String(value)) => F::String(value.clone()),
+		(
+			K::Bool
+			| K::PackedBool0
+			| K::PackedBool1
+			| K::PackedBool2
+			| K::PackedBool3
+			| K::PackedBool4
+			| K::PackedBool5
+			| K::PackedBool6
+			| K::PackedBool7,
+			F::Bool(value),
+		) => F::Bool(*value),
+		(K::Float32, F::F32(value)) => F::F32(*value),
+		(K::Float32, field) => {
+			let value = integer(field)?;
+			let float = value as f32;
+			if float as i128 != value {
+				return None;
+			}
+			F::F32(float)
+		}
+		(K::Int8, field) => F::I8(integer(field)?.try_into().ok()?),
+		(K::Int16, field) => F::I16(integer(field)?.try_into().ok()?),
+		(K::Int32, field) => F::I32(integer(field)?.try_into().ok()?),
+		(K::Int64, field) => F::I64(integer(field)?.try_into().ok()?),
+		(K::UInt8, field) => F::U8(integer(field)?.try_into().ok()?),
+		(K::UInt16, field) => F::U16(integer(field)?.try_into().ok()?),
+		(K::UInt32, field) => F::U32(integer(field)?.try_into().ok()?),
+		(K::UInt64, field) => F::U64(integer(field)?.try_into().ok()?),
+		_ => return None,
+	};
+
+	Some(field)
+}
+
+fn integer(field: &Field) -> Option<i128> {
+	use Field as F;
+
+	let value = match *field {
+		F::I8(value) => value.into(),
+		F::I16(value) => value.into(),
+		F::I32(value) => value.into(),
+		F::I64(value) => value.into(),
+		F::U8(value) => value.into(),
+		F::U16(value) => value.into(),
+		F::U32(value) => value.into(),
+		F::U64(value) => value.into(),
+		F::F32(value) if value.fract() == 0.0 => value as i128,
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		error::{Error, ErrorValue},
+		excel::{fixture::Fixture, Excel, Field, Language, Row},
+		file::exh::{ColumnDefinition, ColumnKind, ExcelHeader, SheetKind},
+		sestring::SeString,
+		Ironworks,
+	};
+
+	use super::{coerce, Overlay, OverlayRow};
+
+	fn ironworks() -> Ironworks {
+		let mut fixture = Fixture::new();
+		fixture.sheet(
+			"Sheet",
+			SheetKind::Default,
+			&[Language::None],
+			&[(0, &[(0, &[10]), (1, &[11])])],
+		);
+		fixture.build()
+	}
+
+	fn value(row: Row) -> u32 {
+		*row.field(0).unwrap().as_u32().unwrap()
+	}
+
+	#[test]
+	fn rows() {
+		let mut overlay = Overlay::new();
+		overlay.rows(
+			"Sheet",
+			Language::None,
+			[
+				OverlayRow::new(1, vec![Field::U32(21)]),
+				OverlayRow::new(5, vec![Field::I64(25)]),
+				OverlayRow::new(6, vec![]),
+			],
+		);
+		let excel = Excel::with().overlay(overlay).build(Arc::new(ironworks()));
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		assert_eq!(value(sheet.row(0).unwrap()), 10);
+		assert_eq!(value(sheet.row(1).unwrap()), 21);
+		assert_eq!(value(sheet.row(5).unwrap()), 25);
+		assert_eq!(value(sheet.row(6).unwrap()), 0);
+		assert!(sheet.row(4).is_err());
+
+		let values = sheet.iter().map(value).collect::<Vec<_>>();
+		assert_eq!(values, vec![10, 21, 25, 0]);
+		assert_eq!(sheet.len().unwrap(), 4);
+	}
+
+	#[test]
+	fn sheet() {
+		let header = ExcelHeader::new(
+			SheetKind::Default,
+			8,
+			vec![
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::Int16, 4),
+			],
+			vec![],
+			0,
+			vec![Language::English.into()],
+		);
+		let mut overlay = Overlay::new();
+		overlay.sheet("Virtual", header).row(
+			"Virtual",
+			Language::English,
+			OverlayRow::new(
+				3,
+				vec![
+					Field::String(SeString::try_from("hi").unwrap()),
+					Field::I8(-2),
+				],
+			),
+		);
+
+		let ironworks = ironworks();
+		let excel = Excel::with()
+			.language(Language::English)
+			.overlay(overlay)
+			.build(&ironworks);
+		assert!(excel.list().unwrap().has("Virtual"));
+
+		let sheet = excel.sheet("Virtual").unwrap();
+		assert_eq!(sheet.len().unwrap(), 1);
+
+		let row = sheet.row(3).unwrap();
+		assert_eq!(
+			row.field(0).unwrap().into_string().unwrap().to_string(),
+			"hi"
+		);
+		assert_eq!(row.field(1).unwrap().into_i16().unwrap(), -2);
+	}
+
+	#[test]
+	fn invalid_field() {
+		let mut overlay = Overlay::new();
+		overlay.row(
+			"Sheet",
+			Language::None,
+			OverlayRow::new(0, vec![Field::I8(-1)]),
+		);
+		let ironworks = ironworks();
+		let excel = Excel::with().overlay(overlay).build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		assert!(matches!(
+			sheet.row(0),
+			Err(Error::Invalid(ErrorValue::Row { row: 0, .. }, _))
+		));
+		assert!(sheet.subrow_all_languages(0, 0).is_err());
+
+		// Other rows on the same page remain readable.
+		assert_eq!(value(sheet.row(1).unwrap()), 11);
+		let results = sheet.try_iter().collect::<Vec<_>>();
+		assert_eq!(results.len(), 2);
+		assert!(results[0].is_err());
+		assert_eq!(value(results.into_iter().last().unwrap().unwrap()), 11);
+	}
+
+	#[test]
+	fn invalid_subrow() {
+		let mut overlay = Overlay::new();
+		overlay.row(
+			"Sheet",
+			Language::None,
+			OverlayRow::subrow(1, 3, vec![Field::U32(21)]),
+		);
+		let ironworks = ironworks();
+		let excel = Excel::with().overlay(overlay).build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		assert!(sheet.subrow(1, 3).is_err());
+		assert_eq!(value(sheet.row(1).unwrap()), 11);
+	}
+
+	#[test]
+	fn float_precision() {
+		let float =
+			|field| coerce(&field, ColumnKind::Float32).map(|field| field.into_f32().unwrap());
+		assert_eq!(float(Field::U32(1 << 24)), Some(16_777_216.0));
+		assert_eq!(float(Field::U32((1 << 24) + 1)), None);
+		assert_eq!(float(Field::I64(-3)), Some(-3.0));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn json() {
+		let rows: Vec<OverlayRow> = serde_json::from_value(serde_json::json!([
+			{ "row_id": 0, "fields": [30] },
+			{ "row_id": 2, "subrow_id": 0, "fields": [32] },
+		]))
+		.unwrap();
+		let mut overlay = Overlay::new();
+		overlay.rows("Sheet", Language::None, rows);
+
+		let ironworks = ironworks();
+		let excel = Excel::with().overlay(overlay).build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		let values = sheet.iter().map(value).collect::<Vec<_>>();
+		assert_eq!(values, vec![30, 11, 32]);
+	}
+}
@@ -1,14 +1,14 @@
-use std::{
-	io::Cursor,
-	sync::{Arc, Mutex},
-};
+use std::{any, fmt, io::Cursor, ops::Range, sync::Arc};
 
 use binrw::{BinReaderExt, BinResult};
 
 use crate::{
 	error::{Error, ErrorValue, Result},
-	excel::field::Field,
-	file::exh,
+	excel::{
+		field::{ConversionError, Field, FromField},
+		language::Language,
+	},
+	file::{exd, exh},
 	sestring::SeString,
 };
 
@@ -34,27 +34,50 @@ impl<'a> From<&'a exh::ColumnDefinition> for ColumnSpecifier<'a> {
 }
 
 /// A (sub)row within an Excel sheet.
-#[derive(Debug)]
+///
+/// Rows share the data of the page they were read from, rather than holding a
+/// copy. Fields are decoded on request, and may be read from multiple threads.
+#[derive(Debug, Clone)]
 pub struct Row {
 	row_id: u32,
 	subrow_id: u16,
+	language: Language,
 
 	header: Arc<exh::ExcelHeader>,
-	data: Mutex<Cursor<Vec<u8>>>,
+	data: RowData,
 }
 
 impl Row {
+	// Build a row holding the provided data, for use when testing.
+	#[cfg(test)]
 	pub(super) fn new(
 		row_id: u32,
 		subrow_id: u16,
+		language: Language,
 		header: Arc<exh::ExcelHeader>,
 		data: Vec<u8>,
+	) -> Self {
+		let mut page = exd::ExcelData::new();
+		page.set_row(row_id, data).unwrap();
+		let range = page.row_range(row_id).unwrap();
+		Self::from_page(row_id, subrow_id, language, header, page.into(), range)
+	}
+
+	// Build a row referencing the specified range of a page's data.
+	pub(super) fn from_page(
+		row_id: u32,
+		subrow_id: u16,
+		language: Language,
+		header: Arc<exh::ExcelHeader>,
+		page: Arc<exd::ExcelData>,
+		range: Range<usize>,
 	) -> Self {
 		Self {
 			row_id,
 			subrow_id,
+			language,
 			header,
-			data: Cursor::new(data).into(),
+			data: RowData { page, range },
 		}
 	}
 
@@ -68,26 +91,71 @@ impl Row {
 		self.subrow_id
 	}
 
+	/// Language this row was read in. This may differ from the requested language
+	/// if it was not available, and a fallback language was used instead.
+	pub fn language(&self) -> Language {
+		self.language
+	}
+
+	pub(super) fn header(&self) -> &Arc<exh::ExcelHeader> {
+		&self.header
+	}
+
+	pub(super) fn data(&self) -> &[u8] {
+		self.data.bytes()
+	}
+
 	/// Read the field at the specified column from this row.
 	pub fn field<'a>(&self, specifier: impl Into<ColumnSpecifier<'a>>) -> Result<Field> {
-		let column = match specifier.into() {
-			ColumnSpecifier::Definition(definition) => definition,
-			ColumnSpecifier::Index(index) => {
-				self.header.columns().get(index).ok_or_else(|| {
-					// TODO: should this have its own value type?
-					Error::NotFound(ErrorValue::Other(format!("Column {index}")))
-				})?
+		let column = self.column(specifier.into())?;
+		Ok(self.read_field(column)?)
+	}
+
+	/// Read the field at the specified column from this row, converted to `T`.
+	/// See [`Field`] for the supported conversions.
+	pub fn get<'a, T: FromField>(&self, specifier: impl Into<ColumnSpecifier<'a>>) -> Result<T> {
+		let column = self.column(specifier.into())?;
+		let field = self.read_field(column)?;
+
+		T::from_field(field).map_err(|error| match error {
+			// Errors raised while converting a field's value are passed through as-is.
+			ConversionError::Value(error) => error,
+			ConversionError::Kind => {
+				let target = any::type_name::<T>()
+					.rsplit("::")
+					.next()
+					.unwrap_or_default();
+				Error::Invalid(
+					ErrorValue::Row {
+						row: self.row_id,
+						subrow: self.subrow_id,
+						sheet: None,
+					},
+					format!(
+						"cannot read {:?} column at offset {} as {target}",
+						column.kind(),
+						column.offset()
+					),
+				)
 			}
-		};
+		})
+	}
 
-		Ok(self.read_field(column)?)
+	fn column<'a>(&'a self, specifier: ColumnSpecifier<'a>) -> Result<&'a exh::ColumnDefinition> {
+		match specifier {
+			ColumnSpecifier::Definition(definition) => Ok(definition),
+			ColumnSpecifier::Index(index) => self.header.columns().get(index).ok_or_else(|| {
+				// TODO: should this have its own value type?
+				Error::NotFound(ErrorValue::Other(format!("Column {index}")))
+			}),
+		}
 	}
 
 	fn read_field(&self, column: &exh::ColumnDefinition) -> BinResult<Field> {
 		use exh::ColumnKind as K;
 		use Field as F;
 
-		let mut cursor = self.data.lock().expect("Data mutex poisoned.");
+		let mut cursor = Cursor::new(self.data());
 
 		cursor.set_position(column.offset().into());
 
@@ -128,3 +196,97 @@ impl Row {
 		Ok(field)
 	}
 }
+
+#[derive(Clone)]
+struct RowData {
+	page: Arc<exd::ExcelData>,
+	range: Range<usize>,
+}
+
+impl RowData {
+	fn bytes(&self) -> &[u8] {
+		&self.page.bytes()[self.range.clone()]
+	}
+}
+
+// Pages are shared between rows, avoid dumping their full contents.
+impl fmt::Debug for RowData {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("RowData")
+			.field("length", &self.range.len())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		excel::{fixture::Fixture, Excel, Language},
+		file::{
+			exd::ExcelData,
+			exh::{ColumnDefinition, ColumnKind, ExcelHeader, SheetKind},
+		},
+	};
+
+	use super::Row;
+
+	#[test]
+	fn subrow_ranges() {
+		let header = Arc::new(ExcelHeader::new(
+			SheetKind::Subrows,
+			2,
+			vec![ColumnDefinition::new(ColumnKind::UInt16, 0)],
+			vec![],
+			0,
+			vec![],
+		));
+
+		let mut page = ExcelData::new();
+		page.set_subrow(1, 0, vec![0, 10]).unwrap();
+		page.set_subrow(1, 3, vec![0, 13]).unwrap();
+		page.set_subrow(2, 0, vec![0, 20]).unwrap();
+		let page = Arc::new(page);
+
+		let subrow = |row_id, subrow_id| {
+			let range = page.subrow_range(row_id, subrow_id).unwrap();
+			let row = Row::from_page(
+				row_id,
+				subrow_id,
+				Language::None,
+				header.clone(),
+				page.clone(),
+				range,
+			);
+			*row.field(0).unwrap().as_u16().unwrap()
+		};
+
+		assert_eq!(subrow(1, 0), 10);
+		assert_eq!(subrow(1, 3), 13);
+		assert_eq!(subrow(2, 0), 20);
+	}
+
+	#[test]
+	fn outlives_cache() {
+		let mut fixture = Fixture::new();
+		fixture.sheet(
+			"Sheet",
+			SheetKind::Default,
+			&[Language::None],
+			&[(0, &[(0, &[1]), (1, &[2])])],
+		);
+		let ironworks = fixture.build();
+		let excel = Excel::new(&ironworks);
+
+		let sheet = excel.sheet("Sheet").unwrap();
+		let row = sheet.row(1).unwrap();
+
+		// Rows share the page data, and remain readable once evicted from the cache.
+		excel.clear_cache();
+		assert_eq!(excel.cache_stats().pages(), 0);
+		assert_eq!(*row.field(0).unwrap().as_u32().unwrap(), 2);
+		assert_eq!(row.data(), 2u32.to_be_bytes());
+	}
+}
@@ -0,0 +1,643 @@
+//! Full-text search over the strings of an Excel database.
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	io::{Seek, Write},
+	ops::Bound,
+};
+
+use binrw::{binrw, BinRead, BinWrite};
+use getset::{CopyGetters, Getters};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::{exh::ColumnKind, File, WriteFile},
+	FileStream,
+};
+
+use super::{excel::Excel, field::Field, language::Language, sheet::RowConfig};
+
+/// Inverted index over the string fields of one or more Excel sheets.
+///
+/// Strings are indexed as plain text, with SeString payloads omitted. Text is
+/// split into lowercase words on any character that is not alphanumeric or an
+/// apostrophe - as such, languages that do not separate words with whitespace
+/// are best queried with [`Matching::Prefix`].
+///
+/// Indexes can be persisted to and read from disk via [`WriteFile`] and
+/// [`File`], avoiding the need to rebuild them on every start.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+	sheets: Vec<String>,
+	documents: Vec<Document>,
+	terms: BTreeMap<String, Vec<u32>>,
+}
+
+impl SearchIndex {
+	/// Build a new, empty index.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Build an index over every sheet in the database.
+	pub fn build(excel: &Excel) -> Result<Self> {
+		let list = excel.list()?;
+		let mut names = list.iter().collect::<Vec<_>>();
+		names.sort_unstable();
+
+		let mut index = Self::new();
+		for name in names {
+			index.insert_sheet(excel, &name)?;
+		}
+
+		Ok(index)
+	}
+
+	/// Add the string fields of a sheet to the index, in every language the sheet
+	/// provides. Sheets that have already been indexed are skipped.
+	pub fn insert_sheet(&mut self, excel: &Excel, name: &str) -> Result<()> {
+		if self.sheets.iter().any(|sheet| sheet == name) {
+			return Ok(());
+		}
+
+		let sheet = excel.sheet(name)?;
+		let columns = sheet
+			.columns()?
+			.iter()
+			.enumerate()
+			.filter(|(_, column)| column.kind() == ColumnKind::String)
+			.map(|(index, _)| index)
+			.collect::<Vec<_>>();
+
+		if columns.is_empty() {
+			self.sheets.push(name.to_string());
+			return Ok(());
+		}
+
+		// Documents are collected before being added, such that a sheet that fails
+		// to read part way through is not left partially indexed.
+		let sheet_index = u32::try_from(self.sheets.len()).unwrap();
+		let mut documents = Vec::new();
+
+		let mut languages = sheet.languages()?;
+		languages.sort_by_key(|&language| u8::from(language));
+
+		for language in languages {
+			let config = RowConfig {
+				language: Some(language),
+				..Default::default()
+			};
+
+			for row in sheet.try_iter_with_options(config) {
+				let row = row?;
+				for &column in &columns {
+					let Field::String(string) = row.field(column)? else {
+						continue;
+					};
+
+					documents.push((
+						Document {
+							sheet: sheet_index,
+							row_id: row.row_id(),
+							subrow_id: row.subrow_id(),
+							column: u16::try_from(column).unwrap(),
+							language,
+						},
+						string.text()?,
+					));
+				}
+			}
+		}
+
+		self.sheets.push(name.to_string());
+		for (document, text) in documents {
+			self.insert_document(document, &text);
+		}
+
+		Ok(())
+	}
+
+	/// Search the index using default options. See [`SearchOptions`].
+	pub fn search(&self, query: &str) -> Vec<SearchHit> {
+		self.with().search(query)
+	}
+
+	/// Build a search against this index with additional options.
+	pub fn with(&self) -> SearchOptions<'_> {
+		SearchOptions::new(self)
+	}
+
+	fn insert_document(&mut self, document: Document, text: &str) {
+		let words = tokenize(text).collect::<BTreeSet<_>>();
+		if words.is_empty() {
+			return;
+		}
+
+		let document_index = u32::try_from(self.documents.len()).unwrap();
+		self.documents.push(document);
+		for word in words {
+			self.terms.entry(word).or_default().push(document_index);
+		}
+	}
+
+	// Collect the documents containing any term matching the provided word.
+	fn matching_documents(&self, word: &str, matching: Matching) -> BTreeSet<u32> {
+		match matching {
+			Matching::Exact => self
+				.terms
+				.get_key_value(word)
+				.into_iter()
+				.flat_map(|(_, documents)| documents)
+				.copied()
+				.collect(),
+
+			Matching::Prefix => self
+				.terms
+				.range::<str, _>((Bound::Included(word), Bound::Unbounded))
+				.take_while(|(term, _)| term.starts_with(word))
+				.flat_map(|(_, documents)| documents)
+				.copied()
+				.collect(),
+
+			Matching::Fuzzy(distance) => self
+				.terms
+				.iter()
+				.filter(|(term, _)| within_distance(word, term, distance))
+				.flat_map(|(_, documents)| documents)
+				.copied()
+				.collect(),
+		}
+	}
+}
+
+/// Strategy used to match query words against indexed words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Matching {
+	/// Match words exactly.
+	#[default]
+	Exact,
+	/// Match words starting with the query word.
+	Prefix,
+	/// Match words within the specified Levenshtein distance of the query word.
+	Fuzzy(u32),
+}
+
+/// Options used when searching an index.
+#[derive(Debug)]
+pub struct SearchOptions<'i> {
+	index: &'i SearchIndex,
+
+	matching: Matching,
+	language: Option<Language>,
+	sheet: Option<String>,
+}
+
+impl<'i> SearchOptions<'i> {
+	fn new(index: &'i SearchIndex) -> Self {
+		Self {
+			index,
+			matching: Matching::default(),
+			language: None,
+			sheet: None,
+		}
+	}
+
+	/// Set the strategy used to match query words. Defaults to exact matches.
+	pub fn matching(&mut self, matching: Matching) -> &mut Self {
+		self.matching = matching;
+		self
+	}
+
+	/// Limit results to strings in the specified language.
+	pub fn language(&mut self, language: Language) -> &mut Self {
+		self.language = Some(language);
+		self
+	}
+
+	/// Limit results to strings in the specified sheet.
+	pub fn sheet(&mut self, sheet: impl Into<String>) -> &mut Self {
+		self.sheet = Some(sheet.into());
+		self
+	}
+
+	/// Find the fields containing every word in the query. Results are ordered by
+	/// the order their sheets were indexed, then by language, row, subrow, and
+	/// column.
+	pub fn search(&self, query: &str) -> Vec<SearchHit> {
+		let index = self.index;
+
+		let mut matches = None::<BTreeSet<u32>>;
+		for word in tokenize(query) {
+			let documents = index.matching_documents(&word, self.matching);
+			matches = Some(match matches {
+				None => documents,
+				Some(matches) => matches.intersection(&documents).copied().collect(),
+			});
+		}
+
+		matches
+			.unwrap_or_default()
+			.into_iter()
+			.map(|document_index| &index.documents[usize::try_from(document_index).unwrap()])
+			.filter(|document| self.language.is_none() || self.language == Some(document.language))
+			.filter_map(|document| {
+				let sheet = &index.sheets[usize::try_from(document.sheet).unwrap()];
+				if self.sheet.as_ref().is_some_and(|filter| filter != sheet) {
+					return None;
+				}
+
+				Some(SearchHit {
+					sheet: sheet.clone(),
+					row_id: document.row_id,
+					subrow_id: document.subrow_id,
+					column: document.column.into(),
+					language: document.language,
+				})
+			})
+			.collect()
+	}
+}
+
+/// A field matching a search query.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, CopyGetters)]
+pub struct SearchHit {
+	/// Name of the sheet containing the field.
+	#[get = "pub"]
+	sheet: String,
+	/// ID of the row containing the field.
+	#[get_copy = "pub"]
+	row_id: u32,
+	/// Subrow ID of the row containing the field.
+	#[get_copy = "pub"]
+	subrow_id: u16,
+	/// Index of the field's column within the sheet.
+	#[get_copy = "pub"]
+	column: usize,
+	/// Language of the field's string.
+	#[get_copy = "pub"]
+	language: Language,
+}
+
+// Location of an indexed string.
+#[binrw]
+#[derive(Debug, Clone, Copy)]
+#[brw(little)]
+struct Document {
+	sheet: u32,
+	row_id: u32,
+	subrow_id: u16,
+	column: u16,
+	#[br(try_map = |language: u8| Language::try_from(language))]
+	#[bw(map = |language: &Language| u8::from(*language))]
+	language: Language,
+}
+
+// On-disk layout of a search index.
+#[binrw]
+#[derive(Debug)]
+#[brw(little, magic = b"IWSI")]
+struct IndexLayout {
+	#[br(assert(version == IndexLayout::VERSION, "unsupported search index version {}", version))]
+	version: u32,
+
+	#[br(temp)]
+	#[bw(calc = u32::try_from(sheets.len()).unwrap())]
+	sheet_count: u32,
+	#[br(count = sheet_count)]
+	sheets: Vec<LayoutString>,
+
+	#[br(temp)]
+	#[bw(calc = u32::try_from(documents.len()).unwrap())]
+	document_count: u32,
+	#[br(count = document_count)]
+	documents: Vec<Document>,
+
+	#[br(temp)]
+	#[bw(calc = u32::try_from(terms.len()).unwrap())]
+	term_count: u32,
+	#[br(count = term_count)]
+	terms: Vec<LayoutTerm>,
+}
+
+impl IndexLayout {
+	const VERSION: u32 = 1;
+}
+
+#[binrw]
+#[derive(Debug)]
+#[brw(little)]
+struct LayoutTerm {
+	term: LayoutString,
+
+	#[br(temp)]
+	#[bw(calc = u32::try_from(documents.len()).unwrap())]
+	document_count: u32,
+	#[br(count = document_count)]
+	documents: Vec<u32>,
+}
+
+#[binrw]
+#[derive(Debug)]
+#[brw(little)]
+struct LayoutString {
+	#[br(temp)]
+	#[bw(calc = u32::try_from(value.len()).unwrap())]
+	length: u32,
+	#[br(count = length, try_map = String::from_utf8)]
+	#[bw(map = |value: &String| value.as_bytes().to_vec())]
+	value: String,
+}
+
+impl File for SearchIndex {
+	fn read(mut stream: impl FileStream) -> Result<Self> {
+		let layout = <IndexLayout as BinRead>::read(&mut stream)?;
+
+		// Documents and terms index into the preceding tables - ensure they're in
+		// range, such that searches do not need to check.
+		let invalid =
+			|message: String| Error::Invalid(ErrorValue::Other("search index".into()), message);
+
+		let sheet_count = layout.sheets.len();
+		if let Some(document) = layout
+			.documents
+			.iter()
+			.find(|document| usize::try_from(document.sheet).unwrap() >= sheet_count)
+		{
+			return Err(invalid(format!(
+				"document references sheet {} of {sheet_count}",
+				document.sheet
+			)));
+		}
+
+		let document_count = layout.documents.len();
+		for term in &layout.terms {
+			if let Some(document) = term
+				.documents
+				.iter()
+				.find(|&&document| usize::try_from(document).unwrap() >= document_count)
+			{
+				return Err(invalid(format!(
+					"term {:?} references document {document} of {document_count}",
+					term.term.value
+				)));
+			}
+		}
+
+		Ok(Self {
+			sheets: layout.sheets.into_iter().map(|sheet| sheet.value).collect(),
+			documents: layout.documents,
+			terms: layout
+				.terms
+				.into_iter()
+				.map(|term| (term.term.value, term.documents))
+				.collect(),
+		})
+	}
+}
+
+impl WriteFile for SearchIndex {
+	fn write(&self, mut stream: impl Write + Seek) -> Result<()> {
+		let layout = IndexLayout {
+			version: IndexLayout::VERSION,
+			sheets: self
+				.sheets
+				.iter()
+				.map(|sheet| LayoutString {
+					value: sheet.clone(),
+				})
+				.collect(),
+			documents: self.documents.clone(),
+			terms: self
+				.terms
+				.iter()
+				.map(|(term, documents)| LayoutTerm {
+					term: LayoutString {
+						value: term.clone(),
+					},
+					documents: documents.clone(),
+				})
+				.collect(),
+		};
+
+		Ok(BinWrite::write(&layout, &mut stream)?)
+	}
+}
+
+// Split text into lowercase words.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+	text.split(|character: char| !(character.is_alphanumeric() || is_apostrophe(character)))
+		.map(|word| word.trim_matches(is_apostrophe))
+		.filter(|word| !word.is_empty())
+		.map(|word| {
+			word.chars()
+				.map(|character| match is_apostrophe(character) {
+					true => '\'',
+					false => character,
+				})
+				.flat_map(char::to_lowercase)
+				.collect()
+		})
+}
+
+fn is_apostrophe(character: char) -> bool {
+	matches!(character, '\'' | '\u{2019}')
+}
+
+// Check if the Levenshtein distance between two words is at most `distance`.
+fn within_distance(a: &str, b: &str, distance: u32) -> bool {
+	let a = a.chars().collect::<Vec<_>>();
+	let b = b.chars().collect::<Vec<_>>();
+	let distance = usize::try_from(distance).unwrap_or(usize::MAX);
+	if a.len().abs_diff(b.len()) > distance {
+		return false;
+	}
+
+	let mut previous = (0..=b.len()).collect::<Vec<_>>();
+	for (i, a_char) in a.iter().enumerate() {
+		let mut current = vec![i + 1; b.len() + 1];
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous[j] + usize::from(a_char != b_char);
+			current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+		}
+		// Every path through the remaining rows passes through this one.
+		if current.iter().min().copied().unwrap_or_default() > distance {
+			return false;
+		}
+		previous = current;
+	}
+
+	previous[b.len()] <= distance
+}
+
+#[cfg(test)]
+mod test {
+	use std::{io::Cursor, sync::Arc};
+
+	use crate::{
+		excel::{fixture::Fixture, path, Excel, Field, Language, RowWriter},
+		file::{
+			exd::ExcelData,
+			exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+			File, WriteFile,
+		},
+		sestring::SeString,
+		Ironworks,
+	};
+
+	use super::{tokenize, within_distance, Matching, SearchIndex};
+
+	fn fixture() -> Fixture {
+		let columns = vec![
+			ColumnDefinition::new(ColumnKind::UInt32, 0),
+			ColumnDefinition::new(ColumnKind::String, 4),
+		];
+		let rows = [
+			(0, "Ul'dah Aetheryte", "ウルダハ"),
+			(1, "A ticket to Limsa Lominsa.", "リムサ"),
+			(2, "Travel to ul\u{2019}dah.", "ウルダハへ"),
+		];
+
+		let header = Arc::new(ExcelHeader::new(
+			SheetKind::Default,
+			8,
+			columns,
+			vec![PageDefinition::new(0, 3)],
+			3,
+			vec![Language::English.into(), Language::Japanese.into()],
+		));
+
+		let mut fixture = Fixture::new();
+		fixture.header("Item", &header);
+		for language in [Language::English, Language::Japanese] {
+			let mut data = ExcelData::new();
+			for (row_id, english, japanese) in rows {
+				let text = match language {
+					Language::English => english,
+					_ => japanese,
+				};
+				let mut writer = RowWriter::new(header.clone());
+				writer.set_field(0, Field::U32(row_id)).unwrap();
+				writer
+					.set_field(1, Field::String(SeString::try_from(text).unwrap()))
+					.unwrap();
+				data.set_row(row_id, writer.build()).unwrap();
+			}
+			fixture.page("Item", 0, language, &data);
+		}
+
+		fixture
+	}
+
+	fn ironworks() -> Ironworks {
+		fixture().build()
+	}
+
+	fn rows(hits: Vec<super::SearchHit>) -> Vec<(u32, Language)> {
+		hits.iter()
+			.map(|hit| (hit.row_id(), hit.language()))
+			.collect()
+	}
+
+	#[test]
+	fn search() {
+		let ironworks = ironworks();
+		let index = SearchIndex::build(&Excel::new(&ironworks)).unwrap();
+
+		let hits = index.search("Ul'dah");
+		assert_eq!(
+			rows(hits.clone()),
+			vec![(0, Language::English), (2, Language::English)]
+		);
+		assert_eq!(hits[0].sheet(), "Item");
+		assert_eq!(hits[0].column(), 1);
+
+		assert_eq!(
+			rows(index.search("limsa ticket")),
+			vec![(1, Language::English)]
+		);
+		assert!(index.search("limsa aetheryte").is_empty());
+
+		let hits = index.with().matching(Matching::Prefix).search("lom");
+		assert_eq!(rows(hits), vec![(1, Language::English)]);
+
+		let hits = index
+			.with()
+			.matching(Matching::Prefix)
+			.language(Language::Japanese)
+			.search("ウルダハ");
+		assert_eq!(
+			rows(hits),
+			vec![(0, Language::Japanese), (2, Language::Japanese)]
+		);
+
+		let hits = index.with().matching(Matching::Fuzzy(1)).search("uldah");
+		assert_eq!(
+			rows(hits),
+			vec![(0, Language::English), (2, Language::English)]
+		);
+		assert!(index.with().sheet("Action").search("ul'dah").is_empty());
+	}
+
+	#[test]
+	fn persist() {
+		let ironworks = ironworks();
+		let index = SearchIndex::build(&Excel::new(&ironworks)).unwrap();
+
+		let mut cursor = Cursor::new(Vec::new());
+		index.write(&mut cursor).unwrap();
+		cursor.set_position(0);
+		let read = <SearchIndex as File>::read(cursor).unwrap();
+
+		assert_eq!(read.search("aetheryte"), index.search("aetheryte"));
+		assert_eq!(read.terms, index.terms);
+	}
+
+	#[test]
+	fn insert_sheet_failure() {
+		// English is read after Japanese, failing part way through the sheet.
+		let ironworks = fixture()
+			.file(
+				&path::exd("Item", 0, Language::English),
+				b"invalid".to_vec(),
+			)
+			.build();
+		let excel = Excel::new(&ironworks);
+
+		let mut index = SearchIndex::new();
+		index.insert_sheet(&excel, "Item").unwrap_err();
+		assert!(index.sheets.is_empty());
+		assert!(index.documents.is_empty());
+		assert!(index.terms.is_empty());
+	}
+
+	#[test]
+	fn read_out_of_range() {
+		let persisted = |index: &SearchIndex| {
+			let mut cursor = Cursor::new(Vec::new());
+			index.write(&mut cursor).unwrap();
+			cursor.set_position(0);
+			<SearchIndex as File>::read(cursor)
+		};
+
+		let ironworks = ironworks();
+		let mut index = SearchIndex::build(&Excel::new(&ironworks)).unwrap();
+		persisted(&index).unwrap();
+
+		index.documents[0].sheet = 1;
+		persisted(&index).unwrap_err();
+
+		index.documents[0].sheet = 0;
+		let document_count = u32::try_from(index.documents.len()).unwrap();
+		index.terms.insert("missing".into(), vec![document_count]);
+		persisted(&index).unwrap_err();
+	}
+
+	#[test]
+	fn words() {
+		let words = tokenize("The 'Ul\u{2019}dah' Sun-Dial, 2x").collect::<Vec<_>>();
+		assert_eq!(words, vec!["the", "ul'dah", "sun", "dial", "2x"]);
+
+		assert!(within_distance("uldah", "ul'dah", 1));
+		assert!(!within_distance("uldah", "limsa", 2));
+	}
+}
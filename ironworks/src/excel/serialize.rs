@@ -0,0 +1,254 @@
+//! Serialization of Excel rows and fields via serde.
+
+use std::fmt;
+
+use serde::{
+	de::{self, Deserialize, Deserializer, Visitor},
+	ser::{Error as _, Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer},
+};
+
+use crate::sestring::SeString;
+
+use super::{field::Field, row::Row};
+
+/// Representation used when serializing SeString fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringFormat {
+	/// Text resolved with a default context.
+	#[default]
+	Resolved,
+	/// Tree of text and payload segments. See
+	/// [`SeString::structured`](crate::sestring::SeString::structured).
+	Structured,
+}
+
+/// Serializable view of a row, configurable with builder methods.
+///
+/// Rows are serialized as a struct with `row_id`, `subrow_id`, and `fields`
+/// members. Fields are an ordered list by default, or a map if column names are
+/// provided with [`SerializeRow::names`].
+#[derive(Debug)]
+pub struct SerializeRow<'a> {
+	row: &'a Row,
+	names: Option<&'a [String]>,
+	strings: StringFormat,
+}
+
+impl<'a> SerializeRow<'a> {
+	/// Create a serializable view of a row with default options.
+	pub fn new(row: &'a Row) -> Self {
+		Self {
+			row,
+			names: None,
+			strings: StringFormat::default(),
+		}
+	}
+
+	/// Serialize fields as a map keyed by column name, such as those provided by
+	/// a schema. Names are matched to columns by index, with any columns beyond
+	/// the provided names keyed by their index.
+	pub fn names(&mut self, names: &'a [String]) -> &mut Self {
+		self.names = Some(names);
+		self
+	}
+
+	/// Set the representation used for string fields.
+	pub fn strings(&mut self, format: StringFormat) -> &mut Self {
+		self.strings = format;
+		self
+	}
+}
+
+impl Serialize for SerializeRow<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut row = serializer.serialize_struct("Row", 3)?;
+		row.serialize_field("row_id", &self.row.row_id())?;
+		row.serialize_field("subrow_id", &self.row.subrow_id())?;
+		match self.names {
+			None => row.serialize_field("fields", &FieldList(self))?,
+			Some(names) => row.serialize_field("fields", &FieldMap(self, names))?,
+		}
+		row.end()
+	}
+}
+
+/// Serializes as an ordered list of fields, with resolved strings. See
+/// [`SerializeRow`] for further options.
+impl Serialize for Row {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		SerializeRow::new(self).serialize(serializer)
+	}
+}
+
+/// Serializes as the inner value, with strings resolved to text.
+impl Serialize for Field {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		SerializeField(self, StringFormat::Resolved).serialize(serializer)
+	}
+}
+
+/// Deserializes from a plain value. Negative integers are read as `I64`, other
+/// integers as `U64`, floats as `F32`, and strings as plain text.
+impl<'de> Deserialize<'de> for Field {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(FieldVisitor)
+	}
+}
+
+struct FieldVisitor;
+
+impl Visitor<'_> for FieldVisitor {
+	type Value = Field;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a boolean, number, or string")
+	}
+
+	fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+		Ok(Field::Bool(value))
+	}
+
+	fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+		Ok(match u64::try_from(value) {
+			Ok(value) => Field::U64(value),
+			Err(_) => Field::I64(value),
+		})
+	}
+
+	fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+		Ok(Field::U64(value))
+	}
+
+	fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+		Ok(Field::F32(value as f32))
+	}
+
+	fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+		SeString::try_from(value)
+			.map(Field::String)
+			.map_err(E::custom)
+	}
+}
+
+struct FieldList<'a>(&'a SerializeRow<'a>);
+
+impl Serialize for FieldList<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let SerializeRow { row, strings, .. } = self.0;
+		let count = row.header().columns().len();
+
+		let mut sequence = serializer.serialize_seq(Some(count))?;
+		for index in 0..count {
+			let field = row.field(index).map_err(S::Error::custom)?;
+			sequence.serialize_element(&SerializeField(&field, *strings))?;
+		}
+		sequence.end()
+	}
+}
+
+struct FieldMap<'a>(&'a SerializeRow<'a>, &'a [String]);
+
+impl Serialize for FieldMap<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let (SerializeRow { row, strings, .. }, names) = (self.0, self.1);
+		let count = row.header().columns().len();
+
+		let mut map = serializer.serialize_map(Some(count))?;
+		for index in 0..count {
+			let field = row.field(index).map_err(S::Error::custom)?;
+			let value = SerializeField(&field, *strings);
+			match names.get(index) {
+				Some(name) => map.serialize_entry(name, &value)?,
+				None => map.serialize_entry(&index.to_string(), &value)?,
+			}
+		}
+		map.end()
+	}
+}
+
+struct SerializeField<'a>(&'a Field, StringFormat);
+
+impl Serialize for SerializeField<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use Field as F;
+		match self.0 {
+			F::String(value) => match self.1 {
+				StringFormat::Resolved => value.serialize(serializer),
+				StringFormat::Structured => value.structured().serialize(serializer),
+			},
+			F::Bool(value) => value.serialize(serializer),
+			F::I8(value) => value.serialize(serializer),
+			F::I16(value) => value.serialize(serializer),
+			F::I32(value) => value.serialize(serializer),
+			F::I64(value) => value.serialize(serializer),
+			F::U8(value) => value.serialize(serializer),
+			F::U16(value) => value.serialize(serializer),
+			F::U32(value) => value.serialize(serializer),
+			F::U64(value) => value.serialize(serializer),
+			F::F32(value) => value.serialize(serializer),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use binrw::BinReaderExt;
+	use serde_json::json;
+
+	use crate::{
+		excel::{fixture::Fixture, Excel, Language},
+		file::exh::SheetKind,
+		sestring::SeString,
+	};
+
+	use super::{SerializeRow, StringFormat};
+
+	#[test]
+	fn row() {
+		let mut fixture = Fixture::new();
+		fixture.sheet(
+			"Sheet",
+			SheetKind::Subrows,
+			&[Language::None],
+			&[(0, &[(3, &[10, 11])])],
+		);
+		let ironworks = fixture.build();
+		let excel = Excel::new(&ironworks);
+		let row = excel.sheet("Sheet").unwrap().subrow(3, 1).unwrap();
+
+		assert_eq!(
+			serde_json::to_value(&row).unwrap(),
+			json!({"row_id": 3, "subrow_id": 1, "fields": [11]})
+		);
+
+		let names = vec!["Value".to_string()];
+		assert_eq!(
+			serde_json::to_value(SerializeRow::new(&row).names(&names)).unwrap(),
+			json!({"row_id": 3, "subrow_id": 1, "fields": {"Value": 11}})
+		);
+		assert_eq!(
+			serde_json::to_value(SerializeRow::new(&row).names(&[])).unwrap(),
+			json!({"row_id": 3, "subrow_id": 1, "fields": {"0": 11}})
+		);
+	}
+
+	#[test]
+	fn strings() {
+		// "a", new line payload, "b".
+		let bytes = b"a\x02\x10\x01\x03b\0";
+		let string = Cursor::new(bytes).read_be::<SeString>().unwrap();
+
+		assert_eq!(serde_json::to_value(&string).unwrap(), json!("a\nb"));
+		assert_eq!(
+			serde_json::to_value(string.structured()).unwrap(),
+			json!([
+				{"Text": "a"},
+				{"Payload": {"kind": "NewLine", "arguments": []}},
+				{"Text": "b"},
+			])
+		);
+		assert_eq!(StringFormat::default(), StringFormat::Resolved);
+	}
+}
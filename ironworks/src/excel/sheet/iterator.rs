@@ -1,37 +1,58 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt::Debug,
+	sync::Arc,
+};
 
 use crate::{
 	error::{Error, ErrorValue, Result},
-	excel::SheetMetadata,
+	excel::{Language, SheetMetadata},
 	file::{exd, exh},
 };
 
-use super::{row_options::RowConfig, Sheet};
+use super::{row_options::RowConfig, sheet::subrow_ids, Sheet};
 
 /// An iterator that iterates over the rows of an excel sheet.
+///
+/// Iteration ends at the first error encountered. Use [`TrySheetIterator`] to
+/// observe errors while iterating.
 #[derive(Debug)]
 pub struct SheetIterator<'i, S> {
-	sheet: &'i Sheet<'i, S>,
+	sheet: Sheet<'i, S>,
 	config: RowConfig,
 
 	page_index: usize,
 	row_index: usize,
-	subrow_id: u16,
+	subrow_index: usize,
 
-	subrow_max: Option<u16>,
+	subrow_ids: Option<Vec<u16>>,
+	page_end: Option<usize>,
+	finished: bool,
 }
 
 impl<'i, S: SheetMetadata> SheetIterator<'i, S> {
-	pub(super) fn new(sheet: &'i Sheet<S>, config: RowConfig) -> Self {
+	pub(super) fn new(sheet: Sheet<'i, S>, config: RowConfig) -> Self {
 		SheetIterator {
 			sheet,
 			config,
 
 			page_index: 0,
 			row_index: 0,
-			subrow_id: 0,
+			subrow_index: 0,
 
-			subrow_max: None,
+			subrow_ids: None,
+			page_end: None,
+			finished: false,
+		}
+	}
+
+	// Create an iterator over the rows of a single page.
+	#[cfg(feature = "rayon")]
+	pub(super) fn for_page(sheet: Sheet<'i, S>, config: RowConfig, page_index: usize) -> Self {
+		SheetIterator {
+			page_index,
+			page_end: Some(page_index + 1),
+			..Self::new(sheet, config)
 		}
 	}
 }
@@ -40,90 +61,103 @@ impl<S: SheetMetadata> Iterator for SheetIterator<'_, S> {
 	type Item = S::Row;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		// If we've walked past the last page, stop the iterator.
-		let page_count = self.sheet.header().ok()?.pages().len();
-		if self.page_index >= page_count {
-			return None;
-		}
-
-		let mut row = Err(Error::NotFound(ErrorValue::Row {
-			row: 0,
-			subrow: 0,
-			sheet: None,
-		}));
-
-		while let Err(Error::NotFound(ErrorValue::Row { .. })) = row {
-			row = self.sheet.subrow_with_options(
-				self.row_id().ok()?,
-				self.subrow_id,
-				self.config.clone(),
-			);
-			self.step().ok()?;
-		}
-
-		row.ok()
+		self.try_next().ok().flatten()
 	}
 }
 
 impl<S: SheetMetadata> SheetIterator<'_, S> {
-	fn step(&mut self) -> Result<()> {
-		self.subrow_id += 1;
+	// Fetch the next row in the sheet. Errors are positioned such that calling
+	// this again will continue from the next readable row, with pages that fail
+	// to load skipped in their entirety.
+	fn try_next(&mut self) -> Result<Option<S::Row>> {
+		loop {
+			if self.finished {
+				return Ok(None);
+			}
 
-		// If the subrow bounds have been exceeded, move on to the next row.
-		if self.subrow_id > self.subrow_max()? {
-			self.subrow_id = 0;
-			self.subrow_max = None;
-			self.row_index += 1;
-		}
+			// If we've walked past the last page, stop the iterator. Without a header,
+			// there's no way to continue.
+			let header = match self.sheet.header() {
+				Ok(header) => header,
+				Err(error) => {
+					self.finished = true;
+					return Err(error);
+				}
+			};
+			let page_count = header
+				.pages()
+				.len()
+				.min(self.page_end.unwrap_or(usize::MAX));
+			if self.page_index >= page_count {
+				return Ok(None);
+			}
 
-		// If the page bounds have been exceeded, move on to the next page.
-		if self.row_index >= self.page()?.rows().len() {
-			self.row_index = 0;
-			self.page_index += 1;
-		}
+			let page = match self.page() {
+				Ok(page) => page,
+				Err(error) => {
+					self.next_page();
+					return Err(error);
+				}
+			};
 
-		Ok(())
-	}
+			let Some(row_id) = page.rows().get(self.row_index).map(|row| row.id()) else {
+				self.next_page();
+				continue;
+			};
 
-	fn subrow_max(&mut self) -> Result<u16> {
-		// Fetch the count of subrows for this row. It's cached to avoid subrow sheets requiring multiple lookups.
-		let count = match self.sheet.kind()? {
-			exh::SheetKind::Subrows => match self.subrow_max {
-				Some(value) => value,
-				None => {
-					// TODO: this is reading the page out twice, which is really dumb. Expose more data via exd and move logic to excel to avoid this shit.
-					let row_id = self.row_id()?;
-					let page = self.page()?;
+			let subrow_id = match self.next_subrow_id(&page, &header, row_id) {
+				Ok(Some(subrow_id)) => subrow_id,
+				Ok(None) => {
+					self.next_row();
+					continue;
+				}
+				Err(error) => {
+					self.next_row();
+					return Err(error);
+				}
+			};
 
-					// If we get a row not found, we can assume that there are "zero" subrows, in an effort to skip this row.
-					let subrow_max = match page.subrow_max(row_id) {
-						Err(Error::NotFound(ErrorValue::Row { .. })) => Ok(0),
-						other => other,
-					}
-					.expect("failed to read subrow count while iterating");
+			return self
+				.sheet
+				.subrow_with_options(row_id, subrow_id, self.config.clone())
+				.map(Some);
+		}
+	}
 
-					*self.subrow_max.insert(subrow_max)
-				}
-			},
-			_ => 1,
+	// Fetch the ID of the next subrow to read from the current row, if any remain.
+	// Subrow IDs are read once per row, from the row's header within the page.
+	fn next_subrow_id(
+		&mut self,
+		page: &exd::ExcelData,
+		header: &exh::ExcelHeader,
+		row_id: u32,
+	) -> Result<Option<u16>> {
+		let subrow_ids = match &mut self.subrow_ids {
+			Some(subrow_ids) => subrow_ids,
+			None => self.subrow_ids.insert(subrow_ids(page, header, row_id)?),
 		};
-		Ok(count)
+
+		let subrow_id = subrow_ids.get(self.subrow_index).copied();
+		self.subrow_index += 1;
+		Ok(subrow_id)
 	}
 
-	fn row_id(&self) -> Result<u32> {
-		let id = self
-			.page()?
-			.rows()
-			.get(self.row_index)
-			.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Row {}", self.row_index))))?
-			.id();
+	fn next_row(&mut self) {
+		self.subrow_index = 0;
+		self.subrow_ids = None;
+		self.row_index += 1;
+	}
 
-		Ok(id)
+	fn next_page(&mut self) {
+		self.subrow_index = 0;
+		self.subrow_ids = None;
+		self.row_index = 0;
+		self.page_index += 1;
 	}
 
 	fn page(&self) -> Result<Arc<exd::ExcelData>> {
 		self.sheet
-			.page(self.page_definition()?.start_id(), self.config.language)
+			.page(self.page_definition()?.start_id(), &self.config)
 	}
 
 	fn page_definition(&self) -> Result<exh::PageDefinition> {
@@ -138,3 +172,416 @@ impl<S: SheetMetadata> SheetIterator<'_, S> {
 			.copied()
 	}
 }
+
+/// An iterator over the rows of an excel sheet that yields errors encountered
+/// while reading, rather than ending early.
+///
+/// Rows that fail to populate are reported with their row and subrow IDs, and
+/// iteration continues with the following row. Pages that cannot be read are
+/// reported once, and skipped.
+#[derive(Debug)]
+pub struct TrySheetIterator<'i, S> {
+	inner: SheetIterator<'i, S>,
+}
+
+impl<'i, S: SheetMetadata> TrySheetIterator<'i, S> {
+	pub(super) fn new(sheet: Sheet<'i, S>, config: RowConfig) -> Self {
+		Self {
+			inner: SheetIterator::new(sheet, config),
+		}
+	}
+
+	#[cfg(feature = "rayon")]
+	pub(super) fn for_page(sheet: Sheet<'i, S>, config: RowConfig, page_index: usize) -> Self {
+		Self {
+			inner: SheetIterator::for_page(sheet, config, page_index),
+		}
+	}
+}
+
+impl<S: SheetMetadata> Iterator for TrySheetIterator<'_, S> {
+	type Item = Result<S::Row>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.try_next().transpose()
+	}
+}
+
+/// An iterator over the rows of an excel sheet, yielding each row ID alongside
+/// all of the row's subrows.
+///
+/// Errors encountered while reading are yielded, and iteration continues with
+/// the following row. Pages that cannot be read are reported once, and skipped.
+#[derive(Debug)]
+pub struct GroupedIterator<'i, S> {
+	sheet: Sheet<'i, S>,
+	config: RowConfig,
+
+	page_index: usize,
+	row_index: usize,
+	finished: bool,
+}
+
+impl<'i, S: SheetMetadata> GroupedIterator<'i, S> {
+	pub(super) fn new(sheet: Sheet<'i, S>, config: RowConfig) -> Self {
+		Self {
+			sheet,
+			config,
+
+			page_index: 0,
+			row_index: 0,
+			finished: false,
+		}
+	}
+
+	fn next_page(&mut self) {
+		self.page_index += 1;
+		self.row_index = 0;
+	}
+}
+
+impl<S: SheetMetadata> Iterator for GroupedIterator<'_, S> {
+	type Item = Result<(u32, Vec<S::Row>)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if self.finished {
+				return None;
+			}
+
+			// Without a header, there's no way to continue.
+			let header = match self.sheet.header() {
+				Ok(header) => header,
+				Err(error) => {
+					self.finished = true;
+					return Some(Err(error));
+				}
+			};
+			let definition = header.pages().get(self.page_index)?;
+
+			let page = match self.sheet.page(definition.start_id(), &self.config) {
+				Ok(page) => page,
+				Err(error) => {
+					self.next_page();
+					return Some(Err(error));
+				}
+			};
+
+			let Some(row_id) = page.rows().get(self.row_index).map(|row| row.id()) else {
+				self.next_page();
+				continue;
+			};
+			self.row_index += 1;
+
+			return Some(
+				self.sheet
+					.subrows_with_options(row_id, self.config.clone())
+					.map(|rows| (row_id, rows)),
+			);
+		}
+	}
+}
+
+/// An iterator over the rows of an excel sheet, yielding each row in every
+/// language supported by the sheet.
+///
+/// Errors encountered while reading are yielded, and iteration continues with
+/// the following row. Pages that cannot be read are reported once, and skipped.
+#[derive(Debug)]
+pub struct LanguagesIterator<'i, S> {
+	sheet: Sheet<'i, S>,
+	page_index: usize,
+	pending: VecDeque<(u32, u16)>,
+	finished: bool,
+}
+
+impl<'i, S: SheetMetadata> LanguagesIterator<'i, S> {
+	pub(super) fn new(sheet: Sheet<'i, S>) -> Self {
+		Self {
+			sheet,
+			page_index: 0,
+			pending: VecDeque::new(),
+			finished: false,
+		}
+	}
+
+	// Queue the (sub)row IDs present in the next page of the sheet.
+	fn next_page(&mut self) -> Result<bool> {
+		let header = match self.sheet.header() {
+			Ok(header) => header,
+			Err(error) => {
+				self.finished = true;
+				return Err(error);
+			}
+		};
+
+		let Some(definition) = header.pages().get(self.page_index).copied() else {
+			return Ok(false);
+		};
+		self.page_index += 1;
+
+		// Row IDs are shared between languages, read them from the first language
+		// with data available for this page.
+		let mut page = None;
+		for language in self.sheet.languages()? {
+			match self
+				.sheet
+				.page(definition.start_id(), &RowConfig::strict(language))
+			{
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => {
+					page = Some(other?);
+					break;
+				}
+			}
+		}
+		let Some(page) = page else {
+			return Ok(true);
+		};
+
+		for row in page.rows() {
+			let subrow_ids = subrow_ids(&page, &header, row.id())?;
+			self.pending.extend(
+				subrow_ids
+					.into_iter()
+					.map(|subrow_id| (row.id(), subrow_id)),
+			);
+		}
+
+		Ok(true)
+	}
+}
+
+impl<S: SheetMetadata> Iterator for LanguagesIterator<'_, S> {
+	type Item = Result<HashMap<Language, S::Row>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some((row_id, subrow_id)) = self.pending.pop_front() {
+				match self.sheet.subrow_all_languages(row_id, subrow_id) {
+					Err(Error::NotFound(ErrorValue::Row { .. })) => continue,
+					other => return Some(other),
+				}
+			}
+
+			if self.finished {
+				return None;
+			}
+
+			match self.next_page() {
+				Ok(true) => continue,
+				Ok(false) => return None,
+				Err(error) => return Some(Err(error)),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{sync::Arc, thread};
+
+	use crate::{
+		error::{Error, ErrorValue},
+		excel::{fixture::Fixture, Excel, Language, Row, SheetMetadata},
+		file::exh::SheetKind,
+		Ironworks,
+	};
+
+	fn ironworks(corrupt: bool) -> Ironworks {
+		let mut fixture = Fixture::new();
+		fixture
+			.sheet(
+				"Default",
+				SheetKind::Default,
+				&[Language::None],
+				&[
+					(0, &[(0, &[10]), (1, &[11])]),
+					(2, &[(2, &[12])]),
+					(3, &[(4, &[14])]),
+				],
+			)
+			.sheet(
+				"Subrows",
+				SheetKind::Subrows,
+				&[Language::None],
+				&[(0, &[(0, &[0, 1]), (1, &[10])])],
+			);
+		if corrupt {
+			fixture.file("exd/Default_2.exd", vec![0; 4]);
+		}
+		fixture.build()
+	}
+
+	fn value(row: Row) -> u32 {
+		*row.field(0).unwrap().as_u32().unwrap()
+	}
+
+	// Reads rows as their value, failing on the specified value.
+	struct FailOn(u32);
+	impl SheetMetadata for FailOn {
+		fn name(&self) -> String {
+			"Default".into()
+		}
+
+		type Row = u32;
+		type Error = std::fmt::Error;
+		fn populate_row(&self, row: Row) -> Result<Self::Row, Self::Error> {
+			match value(row) {
+				value if value == self.0 => Err(std::fmt::Error),
+				value => Ok(value),
+			}
+		}
+	}
+
+	#[test]
+	fn iterate() {
+		let ironworks = ironworks(false);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+
+		let values = sheet.iter().map(value).collect::<Vec<_>>();
+		assert_eq!(values, vec![10, 11, 12, 14]);
+
+		let values = sheet
+			.try_iter()
+			.map(|row| value(row.unwrap()))
+			.collect::<Vec<_>>();
+		assert_eq!(values, vec![10, 11, 12, 14]);
+	}
+
+	#[test]
+	fn iterate_subrows() {
+		let ironworks = ironworks(false);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Subrows").unwrap();
+
+		let ids = sheet
+			.try_iter()
+			.map(|row| {
+				let row = row.unwrap();
+				(row.row_id(), row.subrow_id())
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(ids, vec![(0, 0), (0, 1), (1, 0)]);
+	}
+
+	#[test]
+	fn grouped() {
+		let ironworks = ironworks(false);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Subrows").unwrap();
+
+		let groups = sheet
+			.iter_grouped()
+			.map(Result::unwrap)
+			.map(|(row_id, rows)| (row_id, rows.into_iter().map(value).collect::<Vec<_>>()))
+			.collect::<Vec<_>>();
+		assert_eq!(groups, vec![(0, vec![0, 1]), (1, vec![10])]);
+
+		let values = sheet.subrows(0).unwrap().into_iter().map(value);
+		assert_eq!(values.collect::<Vec<_>>(), vec![0, 1]);
+
+		let sheet = excel.sheet("Default").unwrap();
+		let values = sheet.subrows(2).unwrap().into_iter().map(value);
+		assert_eq!(values.collect::<Vec<_>>(), vec![12]);
+	}
+
+	#[test]
+	fn corrupt_page() {
+		let ironworks = ironworks(true);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+
+		// Infallible iteration stops at the corrupt page.
+		let values = sheet.iter().map(value).collect::<Vec<_>>();
+		assert_eq!(values, vec![10, 11]);
+
+		// Fallible iteration reports the page, and continues past it.
+		let results = sheet.try_iter().collect::<Vec<_>>();
+		assert_eq!(results.len(), 4);
+		assert!(results[2].is_err());
+		assert_eq!(value(results.into_iter().last().unwrap().unwrap()), 14);
+
+		// Grouped iteration reports the page, and continues past it.
+		let results = sheet.iter_grouped().collect::<Vec<_>>();
+		assert_eq!(results.len(), 4);
+		assert!(results[2].is_err());
+		let (row_id, rows) = results.into_iter().last().unwrap().unwrap();
+		assert_eq!(row_id, 4);
+		assert_eq!(rows.into_iter().map(value).collect::<Vec<_>>(), vec![14]);
+	}
+
+	#[test]
+	fn populate_failure() {
+		let ironworks = ironworks(false);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet(FailOn(11)).unwrap();
+
+		let results = sheet.try_iter().collect::<Vec<_>>();
+		assert_eq!(results.len(), 4);
+		assert!(matches!(
+			results[1],
+			Err(Error::Invalid(
+				ErrorValue::Row {
+					row: 1,
+					subrow: 0,
+					..
+				},
+				_
+			))
+		));
+		assert_eq!(results[3].as_ref().unwrap(), &14);
+	}
+
+	#[test]
+	fn owned() {
+		let excel = Excel::new(Arc::new(ironworks(false)));
+		let iterator = excel.sheet("Default").unwrap().into_iter();
+
+		// Owned iterators outlive the database and sheet they were created from.
+		drop(excel);
+		let values = thread::spawn(move || iterator.map(value).collect::<Vec<_>>())
+			.join()
+			.unwrap();
+		assert_eq!(values, vec![10, 11, 12, 14]);
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn parallel() {
+		use rayon::prelude::*;
+
+		let ironworks = ironworks(false);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+
+		let mut values = sheet
+			.par_iter()
+			.map(|row| value(row.unwrap()))
+			.collect::<Vec<_>>();
+		values.sort_unstable();
+		assert_eq!(values, vec![10, 11, 12, 14]);
+
+		let values = sheet.par_collect().unwrap().into_iter().map(value);
+		assert_eq!(values.collect::<Vec<_>>(), vec![10, 11, 12, 14]);
+
+		let ironworks = self::ironworks(true);
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+		assert!(sheet.par_collect().is_err());
+		assert!(sheet.par_iter().any(|row| row.is_err()));
+
+		// Header errors are yielded rather than producing an empty iterator.
+		let mut fixture = Fixture::new();
+		fixture
+			.sheet("Default", SheetKind::Default, &[Language::None], &[])
+			.file("exd/Default.exh", vec![0; 4]);
+		let ironworks = fixture.build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+		let rows = sheet.par_iter().collect::<Vec<_>>();
+		assert_eq!(rows.len(), 1);
+		assert!(rows[0].is_err());
+	}
+}
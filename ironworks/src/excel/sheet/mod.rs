@@ -1,9 +1,14 @@
 mod iterator;
+mod page;
+mod query;
 mod row_options;
 mod sheet;
 
+pub(crate) use row_options::RowConfig;
 pub use {
-	iterator::SheetIterator,
+	iterator::{GroupedIterator, LanguagesIterator, SheetIterator, TrySheetIterator},
+	page::PageSummary,
+	query::{ColumnIndex, Predicate, Query},
 	row_options::RowOptions,
 	sheet::{Sheet, SheetCache},
 };
@@ -0,0 +1,22 @@
+use getset::CopyGetters;
+
+use crate::file::exh;
+
+/// Summary of a single data page of a sheet.
+#[derive(Debug, Clone, Copy, CopyGetters)]
+#[get_copy = "pub"]
+pub struct PageSummary {
+	/// Definition of the page as listed by the sheet header.
+	definition: exh::PageDefinition,
+	/// Number of rows present within the page data.
+	row_count: usize,
+}
+
+impl PageSummary {
+	pub(super) fn new(definition: exh::PageDefinition, row_count: usize) -> Self {
+		Self {
+			definition,
+			row_count,
+		}
+	}
+}
@@ -0,0 +1,648 @@
+use std::{
+	cmp::Ordering,
+	collections::{HashMap, HashSet},
+	io::Cursor,
+};
+
+use binrw::BinReaderExt;
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	excel::{field::Field, language::Language, metadata::SheetMetadata, row::ColumnSpecifier},
+	file::exh,
+	sestring::SeString,
+};
+
+use super::{
+	row_options::RowConfig,
+	sheet::{row_data, subrow_ids, Sheet},
+};
+
+/// Comparison made against the value of a column.
+///
+/// Numeric values may be compared against columns of any numeric kind, i.e.
+/// `Predicate::Eq(Field::U32(5))` will match a value of `5` in an `Int8` column.
+/// Strings are compared by their raw bytes.
+#[derive(Debug)]
+pub enum Predicate {
+	/// The column value is equal to the provided value.
+	Eq(Field),
+	/// The column value is not equal to the provided value.
+	Ne(Field),
+	/// The column value is less than the provided value.
+	Lt(Field),
+	/// The column value is less than or equal to the provided value.
+	Le(Field),
+	/// The column value is greater than the provided value.
+	Gt(Field),
+	/// The column value is greater than or equal to the provided value.
+	Ge(Field),
+}
+
+impl Predicate {
+	fn value(&self) -> &Field {
+		match self {
+			Self::Eq(value)
+			| Self::Ne(value)
+			| Self::Lt(value)
+			| Self::Le(value)
+			| Self::Gt(value)
+			| Self::Ge(value) => value,
+		}
+	}
+
+	// Check if the predicate holds given the ordering of the column value
+	// relative to the predicate value. Unordered values only satisfy `Ne`.
+	fn holds(&self, ordering: Option<Ordering>) -> bool {
+		use Ordering as O;
+		match self {
+			Self::Eq(_) => ordering == Some(O::Equal),
+			Self::Ne(_) => ordering != Some(O::Equal),
+			Self::Lt(_) => ordering == Some(O::Less),
+			Self::Le(_) => matches!(ordering, Some(O::Less | O::Equal)),
+			Self::Gt(_) => ordering == Some(O::Greater),
+			Self::Ge(_) => matches!(ordering, Some(O::Greater | O::Equal)),
+		}
+	}
+}
+
+/// Query over the rows of a sheet, filtering by column values.
+///
+/// Filters are evaluated against the raw row data, without reading fields that
+/// are not filtered on. `Eq` filters on columns with a cached [`ColumnIndex`]
+/// are resolved using the index rather than scanning the sheet.
+#[derive(Debug)]
+pub struct Query<'s, S> {
+	sheet: &'s Sheet<'s, S>,
+	config: RowConfig,
+
+	filters: Vec<(ColumnSpecifier<'s>, Predicate)>,
+}
+
+impl<'s, S: SheetMetadata> Query<'s, S> {
+	pub(super) fn new(sheet: &'s Sheet<'s, S>) -> Self {
+		Self {
+			sheet,
+			config: Default::default(),
+			filters: Vec::new(),
+		}
+	}
+
+	/// Set the language to query.
+	pub fn language(&mut self, language: Language) -> &mut Self {
+		self.config.language = Some(language);
+		self
+	}
+
+	/// Set the languages to fall back to if the requested language is not
+	/// available. See [`RowOptions::fallback`](super::RowOptions::fallback).
+	pub fn fallback(&mut self, languages: impl IntoIterator<Item = Language>) -> &mut Self {
+		self.config.fallback = Some(languages.into_iter().collect());
+		self
+	}
+
+	/// Set whether the query should fail if the requested language is not
+	/// available. See [`RowOptions::strict`](super::RowOptions::strict).
+	pub fn strict(&mut self, strict: bool) -> &mut Self {
+		self.config.strict = Some(strict);
+		self
+	}
+
+	/// Filter the query to rows where the value of `column` satisfies `predicate`.
+	pub fn filter(
+		&mut self,
+		column: impl Into<ColumnSpecifier<'s>>,
+		predicate: Predicate,
+	) -> &mut Self {
+		self.filters.push((column.into(), predicate));
+		self
+	}
+
+	/// Find the IDs of (sub)rows matching every filter, as `(row, subrow)`, in
+	/// sheet order.
+	pub fn ids(&self) -> Result<Vec<(u32, u16)>> {
+		let sheet = self.sheet;
+		let header = sheet.header()?;
+
+		let filters = self
+			.filters
+			.iter()
+			.map(|(column, predicate)| {
+				let column = resolve_column(&header, column)?;
+				let value = field_key(predicate.value());
+				if (column.kind() == exh::ColumnKind::String) != matches!(value, Key::String(_)) {
+					return Err(Error::Invalid(
+						ErrorValue::Sheet(sheet.name()),
+						format!(
+							"cannot compare {:?} column at offset {} with {:?}",
+							column.kind(),
+							column.offset(),
+							predicate.value()
+						),
+					));
+				}
+				Ok((column, predicate, value))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		// Narrow down candidate rows using any cached indexes.
+		let mut candidates = None::<Vec<(u32, u16)>>;
+		for (column, predicate, _) in &filters {
+			let Predicate::Eq(value) = predicate else {
+				continue;
+			};
+			let Some(index) = sheet.cached_column_index(column, &self.config)? else {
+				continue;
+			};
+
+			let ids = index.get(value);
+			candidates = Some(match candidates {
+				None => ids.to_vec(),
+				Some(candidates) => {
+					let ids = ids.iter().collect::<HashSet<_>>();
+					candidates
+						.into_iter()
+						.filter(|id| ids.contains(id))
+						.collect()
+				}
+			});
+		}
+
+		let mut matches = vec![];
+		let mut visit = |row_id: u32, subrow_id: u16, data: &[u8]| -> Result<()> {
+			for (column, predicate, value) in &filters {
+				let key = read_key(data, header.row_size(), column)
+					.ok_or_else(|| out_of_bounds(sheet.name(), row_id, subrow_id, column))?;
+				if !predicate.holds(compare(&key, value)) {
+					return Ok(());
+				}
+			}
+			matches.push((row_id, subrow_id));
+			Ok(())
+		};
+
+		match candidates {
+			Some(candidates) => {
+				for (row_id, subrow_id) in candidates {
+					let (page, _) = sheet.page_for_row(row_id, subrow_id, &self.config)?;
+					visit(
+						row_id,
+						subrow_id,
+						row_data(&page, &header, row_id, subrow_id)?,
+					)?;
+				}
+			}
+			None => for_each_row(sheet, &header, &self.config, visit)?,
+		}
+
+		Ok(matches)
+	}
+
+	/// Fetch the (sub)rows matching every filter, in sheet order.
+	pub fn rows(&self) -> Result<Vec<S::Row>> {
+		self.ids()?
+			.into_iter()
+			.map(|(row_id, subrow_id)| {
+				self.sheet
+					.subrow_with_options(row_id, subrow_id, self.config.clone())
+			})
+			.collect()
+	}
+}
+
+/// Secondary index over the values of a single column, mapping each value to
+/// the IDs of the (sub)rows containing it.
+#[derive(Debug)]
+pub struct ColumnIndex {
+	kind: exh::ColumnKind,
+	rows: HashMap<IndexKey, Vec<(u32, u16)>>,
+}
+
+impl ColumnIndex {
+	pub(super) fn build<S: SheetMetadata>(
+		sheet: &Sheet<S>,
+		column: &exh::ColumnDefinition,
+		config: &RowConfig,
+	) -> Result<Self> {
+		let header = sheet.header()?;
+
+		let mut rows = HashMap::<IndexKey, Vec<(u32, u16)>>::new();
+		for_each_row(sheet, &header, config, |row_id, subrow_id, data| {
+			let key = read_key(data, header.row_size(), column)
+				.ok_or_else(|| out_of_bounds(sheet.name(), row_id, subrow_id, column))?;
+			if let Some(key) = IndexKey::new(key) {
+				rows.entry(key).or_default().push((row_id, subrow_id));
+			}
+			Ok(())
+		})?;
+
+		Ok(Self {
+			kind: column.kind(),
+			rows,
+		})
+	}
+
+	/// Get the IDs of (sub)rows where the column is equal to `value`, as
+	/// `(row, subrow)`, in sheet order.
+	pub fn get(&self, value: &Field) -> &[(u32, u16)] {
+		use exh::ColumnKind as K;
+
+		// Normalise the value to the representation used by the column.
+		let key = match (self.kind, field_key(value)) {
+			(K::String, key @ Key::String(_)) => Some(key),
+			(K::String, _) | (_, Key::String(_)) => None,
+			(K::Float32, Key::Integer(value)) => Some(Key::Float(value as f64)),
+			(K::Float32, key) => Some(key),
+			(_, Key::Integer(value)) => Some(Key::Integer(value)),
+			(_, Key::Float(value)) => match value.fract() == 0.0 {
+				true => Some(Key::Integer(value as i128)),
+				false => None,
+			},
+		};
+
+		key.and_then(IndexKey::new)
+			.and_then(|key| self.rows.get(&key))
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+}
+
+// Comparable form of a field value.
+#[derive(Debug)]
+enum Key<'a> {
+	Integer(i128),
+	Float(f64),
+	String(&'a [u8]),
+}
+
+// Hashable form of a field value. NaN values are not indexed.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum IndexKey {
+	Integer(i128),
+	Float(u64),
+	String(Vec<u8>),
+}
+
+impl IndexKey {
+	fn new(key: Key) -> Option<Self> {
+		let key = match key {
+			Key::Integer(value) => Self::Integer(value),
+			Key::Float(value) if value.is_nan() => return None,
+			// Ensure that -0.0 and 0.0, which compare equal, share a key.
+			Key::Float(value) => Self::Float(match value == 0.0 {
+				true => 0,
+				false => value.to_bits(),
+			}),
+			Key::String(value) => Self::String(value.to_vec()),
+		};
+		Some(key)
+	}
+}
+
+fn field_key(field: &Field) -> Key<'_> {
+	use Field as F;
+	match field {
+		F::String(value) => Key::String(value.as_bytes()),
+		F::Bool(value) => Key::Integer((*value).into()),
+		F::I8(value) => Key::Integer((*value).into()),
+		F::I16(value) => Key::Integer((*value).into()),
+		F::I32(value) => Key::Integer((*value).into()),
+		F::I64(value) => Key::Integer((*value).into()),
+		F::U8(value) => Key::Integer((*value).into()),
+		F::U16(value) => Key::Integer((*value).into()),
+		F::U32(value) => Key::Integer((*value).into()),
+		F::U64(value) => Key::Integer((*value).into()),
+		F::F32(value) => Key::Float((*value).into()),
+	}
+}
+
+fn compare(a: &Key, b: &Key) -> Option<Ordering> {
+	match (a, b) {
+		(Key::Integer(a), Key::Integer(b)) => Some(a.cmp(b)),
+		(Key::Float(a), Key::Float(b)) => a.partial_cmp(b),
+		(Key::Integer(a), Key::Float(b)) => (*a as f64).partial_cmp(b),
+		(Key::Float(a), Key::Integer(b)) => a.partial_cmp(&(*b as f64)),
+		(Key::String(a), Key::String(b)) => Some(a.cmp(b)),
+		_ => None,
+	}
+}
+
+// Read the value of a column directly from raw row data. Returns `None` if the
+// column lies outside the data.
+fn read_key<'d>(data: &'d [u8], row_size: u16, column: &exh::ColumnDefinition) -> Option<Key<'d>> {
+	use exh::ColumnKind as K;
+
+	let offset = usize::from(column.offset());
+	let bytes = |length: usize| data.get(offset..offset + length);
+
+	let key = match column.kind() {
+		K::String => {
+			let string_offset = u32::from_be_bytes(bytes(4)?.try_into().ok()?);
+			let start = usize::from(row_size) + usize::try_from(string_offset).ok()?;
+			// Payloads may contain null bytes - read the string to find its end.
+			let string = data.get(start..)?;
+			let length = Cursor::new(string)
+				.read_be::<SeString>()
+				.ok()?
+				.as_bytes()
+				.len();
+			Key::String(&string[..length])
+		}
+
+		K::Bool => Key::Integer((bytes(1)?[0] != 0).into()),
+		K::PackedBool0
+		| K::PackedBool1
+		| K::PackedBool2
+		| K::PackedBool3
+		| K::PackedBool4
+		| K::PackedBool5
+		| K::PackedBool6
+		| K::PackedBool7 => {
+			let mask = 1 << (u16::from(column.kind()) - u16::from(K::PackedBool0));
+			Key::Integer(((bytes(1)?[0] & mask) == mask).into())
+		}
+
+		K::Int8 => Key::Integer(i8::from_be_bytes(bytes(1)?.try_into().ok()?).into()),
+		K::Int16 => Key::Integer(i16::from_be_bytes(bytes(2)?.try_into().ok()?).into()),
+		K::Int32 => Key::Integer(i32::from_be_bytes(bytes(4)?.try_into().ok()?).into()),
+		K::Int64 => Key::Integer(i64::from_be_bytes(bytes(8)?.try_into().ok()?).into()),
+
+		K::UInt8 => Key::Integer(bytes(1)?[0].into()),
+		K::UInt16 => Key::Integer(u16::from_be_bytes(bytes(2)?.try_into().ok()?).into()),
+		K::UInt32 => Key::Integer(u32::from_be_bytes(bytes(4)?.try_into().ok()?).into()),
+		K::UInt64 => Key::Integer(u64::from_be_bytes(bytes(8)?.try_into().ok()?).into()),
+
+		K::Float32 => Key::Float(f32::from_be_bytes(bytes(4)?.try_into().ok()?).into()),
+	};
+
+	Some(key)
+}
+
+fn resolve_column(
+	header: &exh::ExcelHeader,
+	column: &ColumnSpecifier,
+) -> Result<exh::ColumnDefinition> {
+	match column {
+		ColumnSpecifier::Definition(definition) => Ok((*definition).clone()),
+		ColumnSpecifier::Index(index) => header
+			.columns()
+			.get(*index)
+			.cloned()
+			.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Column {index}")))),
+	}
+}
+
+fn out_of_bounds(
+	sheet: String,
+	row_id: u32,
+	subrow_id: u16,
+	column: &exh::ColumnDefinition,
+) -> Error {
+	Error::Invalid(
+		ErrorValue::Row {
+			row: row_id,
+			subrow: subrow_id,
+			sheet: Some(sheet),
+		},
+		format!("column at offset {} exceeds row data", column.offset()),
+	)
+}
+
+// Call `visit` with the raw data of every (sub)row in the sheet.
+fn for_each_row<S: SheetMetadata>(
+	sheet: &Sheet<S>,
+	header: &exh::ExcelHeader,
+	config: &RowConfig,
+	mut visit: impl FnMut(u32, u16, &[u8]) -> Result<()>,
+) -> Result<()> {
+	for page_definition in header.pages() {
+		let page = sheet.page(page_definition.start_id(), config)?;
+
+		for row in page.rows() {
+			let row_id = row.id();
+			for subrow_id in subrow_ids(&page, header, row_id)? {
+				visit(
+					row_id,
+					subrow_id,
+					row_data(&page, header, row_id, subrow_id)?,
+				)?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use std::{io::Cursor, sync::Arc};
+
+	use binrw::BinReaderExt;
+
+	use crate::{
+		excel::{fixture::Fixture, Excel, Field, Language, RowWriter},
+		file::{
+			exd::ExcelData,
+			exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		},
+		sestring::SeString,
+		Ironworks,
+	};
+
+	use super::Predicate::{Eq, Ge, Lt, Ne};
+
+	fn ironworks() -> Ironworks {
+		let mut fixture = Fixture::new();
+		fixture
+			.sheet(
+				"Default",
+				SheetKind::Default,
+				&[Language::None],
+				&[
+					(0, &[(0, &[5]), (1, &[7]), (2, &[5])]),
+					(3, &[(3, &[9]), (4, &[5])]),
+				],
+			)
+			.sheet(
+				"Subrows",
+				SheetKind::Subrows,
+				&[Language::None],
+				&[(0, &[(0, &[1, 5]), (1, &[5, 2])])],
+			);
+		fixture.build()
+	}
+
+	#[test]
+	fn filter() {
+		let ironworks = ironworks();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+
+		let ids = sheet.query().filter(0, Eq(Field::U32(5))).ids().unwrap();
+		assert_eq!(ids, vec![(0, 0), (2, 0), (4, 0)]);
+
+		let ids = sheet
+			.query()
+			.filter(0, Ge(Field::I8(5)))
+			.filter(0, Ne(Field::F32(5.0)))
+			.ids()
+			.unwrap();
+		assert_eq!(ids, vec![(1, 0), (3, 0)]);
+
+		let rows = sheet.query().filter(0, Lt(Field::U32(6))).rows().unwrap();
+		assert_eq!(rows.len(), 3);
+
+		let columns = sheet.columns().unwrap();
+		let ids = sheet
+			.query()
+			.filter(&columns[0], Eq(Field::U32(9)))
+			.ids()
+			.unwrap();
+		assert_eq!(ids, vec![(3, 0)]);
+
+		assert!(sheet
+			.query()
+			.filter(0, Eq(Field::String("5".try_into().unwrap())))
+			.ids()
+			.is_err());
+		assert!(sheet.query().filter(1, Eq(Field::U32(5))).ids().is_err());
+	}
+
+	#[test]
+	fn subrows() {
+		let ironworks = ironworks();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Subrows").unwrap();
+
+		let ids = sheet.query().filter(0, Eq(Field::U32(5))).ids().unwrap();
+		assert_eq!(ids, vec![(0, 1), (1, 0)]);
+	}
+
+	#[test]
+	fn index() {
+		let ironworks = ironworks();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Default").unwrap();
+
+		let index = sheet.column_index(0).unwrap();
+		assert_eq!(index.get(&Field::U8(5)), &[(0, 0), (2, 0), (4, 0)]);
+		assert_eq!(index.get(&Field::F32(7.0)), &[(1, 0)]);
+		assert!(index.get(&Field::F32(7.5)).is_empty());
+		assert!(index
+			.get(&Field::String("5".try_into().unwrap()))
+			.is_empty());
+
+		// Indexes are shared between instances of a sheet, and used by queries.
+		let sheet = excel.sheet("Default").unwrap();
+		let column = ColumnDefinition::new(ColumnKind::UInt32, 0);
+		let ids = sheet
+			.query()
+			.filter(&column, Eq(Field::U32(5)))
+			.filter(0, Ne(Field::U32(7)))
+			.ids()
+			.unwrap();
+		assert_eq!(ids, vec![(0, 0), (2, 0), (4, 0)]);
+	}
+
+	#[test]
+	fn payload_strings() {
+		// Payloads may contain null bytes, which do not end the string.
+		let strings = [
+			&b"a\x02\x16\x02\x00\x03b\0"[..],
+			&b"a\x02\x16\x02\x00\x03c\0"[..],
+			&b"a\0"[..],
+		]
+		.map(|bytes| Cursor::new(bytes).read_be::<SeString>().unwrap());
+
+		let header = Arc::new(ExcelHeader::new(
+			SheetKind::Default,
+			4,
+			vec![ColumnDefinition::new(ColumnKind::String, 0)],
+			vec![PageDefinition::new(0, 3)],
+			3,
+			vec![Language::None.into()],
+		));
+		let mut data = ExcelData::new();
+		for (row_id, string) in (0..).zip(&strings) {
+			let mut writer = RowWriter::new(header.clone());
+			writer.set_field(0, Field::String(string.clone())).unwrap();
+			data.set_row(row_id, writer.build()).unwrap();
+		}
+
+		let mut fixture = Fixture::new();
+		fixture
+			.header("Strings", &header)
+			.page("Strings", 0, Language::None, &data);
+		let ironworks = fixture.build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Strings").unwrap();
+
+		let ids = |string: &SeString| {
+			sheet
+				.query()
+				.filter(0, Eq(Field::String(string.clone())))
+				.ids()
+				.unwrap()
+		};
+		assert_eq!(ids(&strings[0]), vec![(0, 0)]);
+		assert_eq!(ids(&strings[1]), vec![(1, 0)]);
+		assert_eq!(ids(&strings[2]), vec![(2, 0)]);
+
+		let index = sheet.column_index(0).unwrap();
+		assert_eq!(index.get(&Field::String(strings[0].clone())), &[(0, 0)]);
+		assert_eq!(index.get(&Field::String(strings[1].clone())), &[(1, 0)]);
+	}
+
+	#[test]
+	fn index_fallback() {
+		// Korean is listed in the header, but missing its data page.
+		let mut fixture = Fixture::new();
+		fixture
+			.sheet(
+				"Sheet",
+				SheetKind::Default,
+				&[Language::English],
+				&[(0, &[(0, &[1])])],
+			)
+			.sheet(
+				"Sheet",
+				SheetKind::Default,
+				&[Language::Japanese],
+				&[(0, &[(0, &[2])])],
+			)
+			.header(
+				"Sheet",
+				&ExcelHeader::new(
+					SheetKind::Default,
+					4,
+					vec![ColumnDefinition::new(ColumnKind::UInt32, 0)],
+					vec![PageDefinition::new(0, 1)],
+					1,
+					vec![
+						Language::English.into(),
+						Language::Japanese.into(),
+						Language::Korean.into(),
+					],
+				),
+			);
+		let ironworks = fixture.build();
+		let excel = Excel::with().language(Language::Korean).build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		// Indexes built with one fallback are not used by queries with another.
+		let index = sheet
+			.with()
+			.fallback([Language::English])
+			.column_index(0)
+			.unwrap();
+		assert_eq!(index.get(&Field::U32(1)), &[(0, 0)]);
+
+		let ids = sheet
+			.query()
+			.fallback([Language::Japanese])
+			.filter(0, Eq(Field::U32(2)))
+			.ids()
+			.unwrap();
+		assert_eq!(ids, vec![(0, 0)]);
+	}
+}
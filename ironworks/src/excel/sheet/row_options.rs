@@ -1,9 +1,14 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use std::sync::Arc;
+
 use crate::{
 	error::Result,
-	excel::{language::Language, metadata::SheetMetadata},
+	excel::{language::Language, metadata::SheetMetadata, row::ColumnSpecifier},
 };
 
-use super::{sheet::Sheet, SheetIterator};
+use super::{sheet::Sheet, ColumnIndex, GroupedIterator, SheetIterator, TrySheetIterator};
 
 // TODO: rename? RowBuilder?
 /// Options used when reading a row from a sheet.
@@ -16,6 +21,19 @@ pub struct RowOptions<'s, S> {
 #[derive(Debug, Default, Clone)]
 pub struct RowConfig {
 	pub language: Option<Language>,
+	pub fallback: Option<Vec<Language>>,
+	pub strict: Option<bool>,
+}
+
+impl RowConfig {
+	// Configuration reading exclusively the specified language.
+	pub fn strict(language: Language) -> Self {
+		Self {
+			language: Some(language),
+			fallback: None,
+			strict: Some(true),
+		}
+	}
 }
 
 impl<'s, S: SheetMetadata> RowOptions<'s, S> {
@@ -32,6 +50,20 @@ impl<'s, S: SheetMetadata> RowOptions<'s, S> {
 		self
 	}
 
+	/// Set the languages to fall back to, in order, if the requested language is
+	/// not available. Overrides the fallback chain of the database.
+	pub fn fallback(&mut self, languages: impl IntoIterator<Item = Language>) -> &mut Self {
+		self.config.fallback = Some(languages.into_iter().collect());
+		self
+	}
+
+	/// Set whether reads should fail if the requested language is not available,
+	/// rather than falling back. Overrides the strict mode of the database.
+	pub fn strict(&mut self, strict: bool) -> &mut Self {
+		self.config.strict = Some(strict);
+		self
+	}
+
 	/// Fetch a row from the sheet by ID. If the sheet supports subrows, this will
 	/// return subrow 0.
 	pub fn row(&self, row_id: u32) -> Result<S::Row> {
@@ -44,11 +76,60 @@ impl<'s, S: SheetMetadata> RowOptions<'s, S> {
 			.subrow_with_options(row_id, subrow_id, self.config.clone())
 	}
 
+	/// Fetch every subrow of a row from the sheet by ID. See [`Sheet::subrows`].
+	pub fn subrows(&self, row_id: u32) -> Result<Vec<S::Row>> {
+		self.sheet()
+			.subrows_with_options(row_id, self.config.clone())
+	}
+
+	/// Fetch an index of the values of the specified column. See
+	/// [`Sheet::column_index`].
+	pub fn column_index<'c>(
+		&self,
+		column: impl Into<ColumnSpecifier<'c>>,
+	) -> Result<Arc<ColumnIndex>> {
+		self.sheet()
+			.column_index_with_options(column, self.config.clone())
+	}
+
 	/// Iterate over the rows in this sheet.
-	pub fn iter(&'s self) -> SheetIterator<'s, S> {
+	pub fn iter(&self) -> SheetIterator<'s, S> {
 		self.sheet().iter_with_options(self.config.clone())
 	}
 
+	/// Iterate over the rows in this sheet, grouped by row ID. See
+	/// [`Sheet::iter_grouped`].
+	pub fn iter_grouped(&self) -> GroupedIterator<'s, S> {
+		self.sheet().iter_grouped_with_options(self.config.clone())
+	}
+
+	/// Iterate over the rows in this sheet, yielding any errors encountered while
+	/// reading rather than ending early.
+	pub fn try_iter(&self) -> TrySheetIterator<'s, S> {
+		self.sheet().try_iter_with_options(self.config.clone())
+	}
+
+	/// Iterate over the rows in this sheet in parallel. See [`Sheet::par_iter`].
+	#[cfg(feature = "rayon")]
+	pub fn par_iter(&self) -> impl ParallelIterator<Item = Result<S::Row>> + 's
+	where
+		S: Send + Sync + 's,
+		S::Row: Send,
+	{
+		self.sheet().par_iter_with_options(self.config.clone())
+	}
+
+	/// Read all rows in this sheet in parallel, returning them in sheet order.
+	/// See [`Sheet::par_collect`].
+	#[cfg(feature = "rayon")]
+	pub fn par_collect(&self) -> Result<Vec<S::Row>>
+	where
+		S: Send + Sync,
+		S::Row: Send,
+	{
+		self.sheet().par_collect_with_options(self.config.clone())
+	}
+
 	fn sheet(&self) -> &Sheet<'s, S> {
 		self.sheet
 			.expect("RowOptions created outside a sheet must be passed to a sheet manually.")
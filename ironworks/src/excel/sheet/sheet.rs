@@ -1,18 +1,33 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, ops::Range, sync::Arc};
 
+#[cfg(feature = "rayon")]
+use either::Either;
 use num_enum::TryFromPrimitive;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 use crate::{
 	error::{Error, ErrorValue, Result},
-	excel::{borrowed::Borrowed, language::Language, metadata::SheetMetadata, path, row::Row},
+	excel::{
+		borrowed::Borrowed,
+		cache::PageCache,
+		language::Language,
+		metadata::SheetMetadata,
+		overlay::Overlay,
+		path,
+		row::{ColumnSpecifier, Row},
+		signature::{ColumnMapping, ColumnSignature},
+	},
 	file::{exd, exh},
 	utility::{HashMapCache, HashMapCacheExt, OptionCache, OptionCacheExt},
 	Ironworks,
 };
 
 use super::{
+	page::PageSummary,
+	query::{ColumnIndex, Query},
 	row_options::{RowConfig, RowOptions},
-	SheetIterator,
+	GroupedIterator, LanguagesIterator, SheetIterator, TrySheetIterator,
 };
 
 // TODO: how much should be in this? Arguably the mapper &co might be relevant given that the mapper is required to fill the caches, etc.
@@ -20,17 +35,27 @@ use super::{
 #[derive(Debug, Default)]
 pub struct SheetCache {
 	header: OptionCache<exh::ExcelHeader>,
-	pages: HashMapCache<(u32, Language), exd::ExcelData>,
+	// Indexes are keyed by the language chain they were built with, as pages may
+	// be read in any of its languages.
+	indexes: HashMapCache<(exh::ColumnDefinition, Vec<Language>), ColumnIndex>,
 }
 
 /// A sheet within an Excel database.
+///
+/// Sheets are cheap to clone, with clones sharing their caches. A sheet fetched
+/// from an [`Excel`](crate::excel::Excel) holding an `Arc<Ironworks>` is
+/// `'static`, as are the iterators it creates.
 pub struct Sheet<'i, S> {
-	sheet_metadata: S,
+	sheet_metadata: Arc<S>,
 	default_language: Language,
+	default_fallback: Vec<Language>,
+	default_strict: bool,
 
 	ironworks: Borrowed<'i, Ironworks>,
 
 	cache: Arc<SheetCache>,
+	pages: Arc<PageCache>,
+	overlay: Arc<Overlay>,
 }
 
 impl<S: Debug> Debug for Sheet<'_, S> {
@@ -38,24 +63,52 @@ impl<S: Debug> Debug for Sheet<'_, S> {
 		f.debug_struct("Sheet")
 			.field("sheet_metadata", &self.sheet_metadata)
 			.field("default_language", &self.default_language)
+			.field("default_fallback", &self.default_fallback)
+			.field("default_strict", &self.default_strict)
 			.finish()
 	}
 }
 
+impl<S> Clone for Sheet<'_, S> {
+	fn clone(&self) -> Self {
+		Self {
+			sheet_metadata: self.sheet_metadata.clone(),
+			default_language: self.default_language,
+			default_fallback: self.default_fallback.clone(),
+			default_strict: self.default_strict,
+
+			ironworks: self.ironworks.clone(),
+
+			cache: self.cache.clone(),
+			pages: self.pages.clone(),
+			overlay: self.overlay.clone(),
+		}
+	}
+}
+
 impl<'i, S: SheetMetadata> Sheet<'i, S> {
+	#[allow(clippy::too_many_arguments)]
 	pub(crate) fn new(
 		sheet_metadata: S,
 		default_language: Language,
+		default_fallback: Vec<Language>,
+		default_strict: bool,
 		ironworks: Borrowed<'i, Ironworks>,
 		cache: Arc<SheetCache>,
+		pages: Arc<PageCache>,
+		overlay: Arc<Overlay>,
 	) -> Self {
 		Self {
-			sheet_metadata,
+			sheet_metadata: sheet_metadata.into(),
 			default_language,
+			default_fallback,
+			default_strict,
 
 			ironworks,
 
 			cache,
+			pages,
+			overlay,
 		}
 	}
 
@@ -92,18 +145,212 @@ impl<'i, S: SheetMetadata> Sheet<'i, S> {
 		Ok(columns)
 	}
 
+	/// Number of rows in this sheet, as reported by the sheet header. No pages are
+	/// read to determine the count.
+	pub fn len(&self) -> Result<u32> {
+		Ok(self.header()?.row_count())
+	}
+
+	/// Check if this sheet contains no rows, as reported by the sheet header.
+	pub fn is_empty(&self) -> Result<bool> {
+		Ok(self.len()? == 0)
+	}
+
+	/// List the IDs of all rows in this sheet, in sheet order. Pages are read to
+	/// determine the IDs present, however no rows are decoded.
+	pub fn row_ids(&self) -> Result<Vec<u32>> {
+		let config = RowConfig::default();
+		let mut ids = vec![];
+		for definition in self.header()?.pages() {
+			let page = self.page(definition.start_id(), &config)?;
+			ids.extend(page.rows().iter().map(|row| row.id()));
+		}
+		Ok(ids)
+	}
+
+	/// Check if this sheet contains a row with the specified ID.
+	pub fn contains(&self, row_id: u32) -> Result<bool> {
+		match self.page_for_row(row_id, 0, &Default::default()) {
+			Ok((page, _)) => Ok(page.rows().iter().any(|row| row.id() == row_id)),
+			Err(Error::NotFound(ErrorValue::Row { .. })) => Ok(false),
+			Err(error) => Err(error),
+		}
+	}
+
+	/// Get the number of subrows of the specified row. Rows in sheets without
+	/// subrows contain a single subrow.
+	pub fn subrow_count(&self, row_id: u32) -> Result<u16> {
+		let (page, _) = self.page_for_row(row_id, 0, &Default::default())?;
+		page.subrow_count(row_id)
+	}
+
+	/// Summarise the data pages of this sheet, in sheet order.
+	pub fn pages(&self) -> Result<Vec<PageSummary>> {
+		let config = RowConfig::default();
+		self.header()?
+			.pages()
+			.iter()
+			.map(|&definition| {
+				let page = self.page(definition.start_id(), &config)?;
+				Ok(PageSummary::new(definition, page.rows().len()))
+			})
+			.collect()
+	}
+
+	/// Get the signature of the column layout of this sheet.
+	pub fn signature(&self) -> Result<ColumnSignature> {
+		Ok(ColumnSignature::new(self.columns()?))
+	}
+
+	/// Map the columns of a stored signature to the live column layout of this
+	/// sheet. See [`ColumnSignature::map_to`].
+	pub fn column_mapping(&self, signature: &ColumnSignature) -> Result<ColumnMapping> {
+		Ok(signature.map_to(&self.signature()?))
+	}
+
 	/// Create a row options builder for this sheet.
-	pub fn with(&'i self) -> RowOptions<'i, S> {
+	pub fn with(&self) -> RowOptions<'_, S> {
 		RowOptions::new(self)
 	}
 
 	/// Iterate over the rows in this sheet.
-	pub fn iter(&'i self) -> SheetIterator<'i, S> {
+	pub fn iter(&self) -> SheetIterator<'i, S> {
 		self.iter_with_options(Default::default())
 	}
 
-	pub(super) fn iter_with_options(&'i self, config: RowConfig) -> SheetIterator<'i, S> {
-		SheetIterator::new(self, config)
+	pub(crate) fn iter_with_options(&self, config: RowConfig) -> SheetIterator<'i, S> {
+		SheetIterator::new(self.clone(), config)
+	}
+
+	/// Iterate over the rows in this sheet, yielding each row ID alongside all of
+	/// the row's subrows, or any error encountered while reading them.
+	pub fn iter_grouped(&self) -> GroupedIterator<'i, S> {
+		self.iter_grouped_with_options(Default::default())
+	}
+
+	pub(crate) fn iter_grouped_with_options(&self, config: RowConfig) -> GroupedIterator<'i, S> {
+		GroupedIterator::new(self.clone(), config)
+	}
+
+	/// Iterate over the rows in this sheet, yielding any errors encountered while
+	/// reading rather than ending early.
+	pub fn try_iter(&self) -> TrySheetIterator<'i, S> {
+		self.try_iter_with_options(Default::default())
+	}
+
+	pub(crate) fn try_iter_with_options(&self, config: RowConfig) -> TrySheetIterator<'i, S> {
+		TrySheetIterator::new(self.clone(), config)
+	}
+
+	/// Iterate over the rows in this sheet in parallel. Work is split by page,
+	/// with each page loaded and read concurrently. Rows are yielded in no
+	/// particular order; use [`Sheet::par_collect`] to retain sheet order. As
+	/// with [`Sheet::try_iter`], errors encountered while reading are yielded
+	/// rather than ending iteration early.
+	#[cfg(feature = "rayon")]
+	pub fn par_iter(&self) -> impl ParallelIterator<Item = Result<S::Row>> + 'i
+	where
+		S: Send + Sync + 'i,
+		S::Row: Send,
+	{
+		self.par_iter_with_options(Default::default())
+	}
+
+	#[cfg(feature = "rayon")]
+	pub(super) fn par_iter_with_options(
+		&self,
+		config: RowConfig,
+	) -> impl ParallelIterator<Item = Result<S::Row>> + 'i
+	where
+		S: Send + Sync + 'i,
+		S::Row: Send,
+	{
+		let page_count = match self.header() {
+			Ok(header) => header.pages().len(),
+			Err(error) => return Either::Right(rayon::iter::once(Err(error))),
+		};
+
+		let sheet = self.clone();
+		Either::Left(
+			(0..page_count)
+				.into_par_iter()
+				.flat_map_iter(move |page_index| {
+					TrySheetIterator::for_page(sheet.clone(), config.clone(), page_index)
+				}),
+		)
+	}
+
+	/// Read all rows in this sheet in parallel, returning them in sheet order.
+	/// Fails with the first error encountered while reading.
+	#[cfg(feature = "rayon")]
+	pub fn par_collect(&self) -> Result<Vec<S::Row>>
+	where
+		S: Send + Sync,
+		S::Row: Send,
+	{
+		self.par_collect_with_options(Default::default())
+	}
+
+	#[cfg(feature = "rayon")]
+	pub(super) fn par_collect_with_options(&self, config: RowConfig) -> Result<Vec<S::Row>>
+	where
+		S: Send + Sync,
+		S::Row: Send,
+	{
+		let page_count = self.header()?.pages().len();
+		(0..page_count)
+			.into_par_iter()
+			.flat_map_iter(|page_index| {
+				TrySheetIterator::for_page(self.clone(), config.clone(), page_index)
+			})
+			.collect()
+	}
+
+	/// Create a query over the rows of this sheet, filtering by column values.
+	pub fn query(&self) -> Query<'_, S> {
+		Query::new(self)
+	}
+
+	/// Fetch an index of the values of the specified column. The index is built
+	/// on first use, and cached for use by later calls and [`Query`]s.
+	pub fn column_index<'c>(
+		&self,
+		column: impl Into<ColumnSpecifier<'c>>,
+	) -> Result<Arc<ColumnIndex>> {
+		self.column_index_with_options(column, Default::default())
+	}
+
+	pub(super) fn column_index_with_options<'c>(
+		&self,
+		column: impl Into<ColumnSpecifier<'c>>,
+		config: RowConfig,
+	) -> Result<Arc<ColumnIndex>> {
+		let column = match column.into() {
+			ColumnSpecifier::Definition(definition) => definition.clone(),
+			ColumnSpecifier::Index(index) => self
+				.header()?
+				.columns()
+				.get(index)
+				.cloned()
+				.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Column {index}"))))?,
+		};
+		let languages = self.language_chain(&config)?;
+
+		self.cache
+			.indexes
+			.try_get_or_insert((column.clone(), languages), || {
+				ColumnIndex::build(self, &column, &config)
+			})
+	}
+
+	pub(super) fn cached_column_index(
+		&self,
+		column: &exh::ColumnDefinition,
+		config: &RowConfig,
+	) -> Result<Option<Arc<ColumnIndex>>> {
+		let languages = self.language_chain(config)?;
+		let indexes = self.cache.indexes.lock().unwrap();
+		Ok(indexes.get(&(column.clone(), languages)).cloned())
 	}
 
 	/// Fetch a row from this sheet by ID. In the case of a sheet with subrows,
@@ -117,6 +364,12 @@ impl<'i, S: SheetMetadata> Sheet<'i, S> {
 		self.subrow_with_options(row_id, subrow_id, Default::default())
 	}
 
+	/// Fetch every subrow of a row from this sheet by ID, in page order. In the
+	/// case of a sheet without subrows, this will return the row alone.
+	pub fn subrows(&self, row_id: u32) -> Result<Vec<S::Row>> {
+		self.subrows_with_options(row_id, Default::default())
+	}
+
 	pub(super) fn row_with_options(&self, row_id: u32, config: RowConfig) -> Result<S::Row> {
 		self.subrow_with_options(row_id, 0, config)
 	}
@@ -141,33 +394,106 @@ impl<'i, S: SheetMetadata> Sheet<'i, S> {
 		}
 
 		// Try to read in the page for the requested (sub)row.
-		let page = self.page_for_row(row_id, subrow_id, config.language)?;
+		let (page, language) = self.page_for_row(row_id, subrow_id, &config)?;
+		self.overlay
+			.check_row(&self.name(), &header, row_id, subrow_id, language)?;
+
+		let range = row_range(&page, &header, row_id, subrow_id)?;
+		self.populate(Row::from_page(
+			row_id, subrow_id, language, header, page, range,
+		))
+	}
 
-		let data = match header.kind() {
-			exh::SheetKind::Subrows => page.subrow_data(row_id, subrow_id),
-			_ => page.row_data(row_id),
-		}?;
-		let row = Row::new(row_id, subrow_id, header, data.to_vec());
+	pub(super) fn subrows_with_options(
+		&self,
+		row_id: u32,
+		config: RowConfig,
+	) -> Result<Vec<S::Row>> {
+		let header = self.header()?;
+		let (page, _) = self.page_for_row(row_id, 0, &config)?;
+
+		subrow_ids(&page, &header, row_id)?
+			.into_iter()
+			.map(|subrow_id| self.subrow_with_options(row_id, subrow_id, config.clone()))
+			.collect()
+	}
+
+	/// Fetch a row from this sheet by ID in every language supported by the
+	/// sheet. In the case of a sheet with subrows, this will return subrow 0.
+	pub fn row_all_languages(&self, row_id: u32) -> Result<HashMap<Language, S::Row>> {
+		self.subrow_all_languages(row_id, 0)
+	}
+
+	/// Fetch a row from this sheet by its ID and subrow ID in every language
+	/// supported by the sheet. Languages listed by the sheet without any data
+	/// available are omitted.
+	pub fn subrow_all_languages(
+		&self,
+		row_id: u32,
+		subrow_id: u16,
+	) -> Result<HashMap<Language, S::Row>> {
+		let header = self.header()?;
+
+		let mut rows = HashMap::new();
+		for language in self.languages()? {
+			let config = RowConfig::strict(language);
+			let (page, _) = match self.page_for_row(row_id, subrow_id, &config) {
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => other?,
+			};
+			self.overlay
+				.check_row(&self.name(), &header, row_id, subrow_id, language)?;
+
+			let range = row_range(&page, &header, row_id, subrow_id)?;
+			let row = Row::from_page(row_id, subrow_id, language, header.clone(), page, range);
+			rows.insert(language, self.populate(row)?);
+		}
+
+		if rows.is_empty() {
+			return Err(Error::NotFound(ErrorValue::Row {
+				row: row_id,
+				subrow: subrow_id,
+				sheet: self.name().into(),
+			}));
+		}
+
+		Ok(rows)
+	}
+
+	/// Iterate over the rows in this sheet, yielding each row in every language
+	/// supported by the sheet. Errors encountered while reading are yielded, and
+	/// iteration continues with the following row.
+	pub fn iter_all_languages(&self) -> LanguagesIterator<'i, S> {
+		LanguagesIterator::new(self.clone())
+	}
+
+	pub(super) fn populate(&self, row: Row) -> Result<S::Row> {
+		let error_value = ErrorValue::Row {
+			row: row.row_id(),
+			subrow: row.subrow_id(),
+			sheet: self.name().into(),
+		};
 
 		self.sheet_metadata
 			.populate_row(row)
-			.map_err(|error| Error::Invalid(row_error_value(), error.to_string()))
+			.map_err(|error| Error::Invalid(error_value, error.to_string()))
 	}
 
 	pub(super) fn header(&self) -> Result<Arc<exh::ExcelHeader>> {
 		self.cache.header.try_get_or_insert(|| {
-			let path = path::exh(&self.name());
-			self.ironworks.file(&path)
+			let name = self.name();
+			self.overlay
+				.header(&name, || self.ironworks.file(&path::exh(&name)))
 		})
 	}
 
 	// TODO: not a fan of the subrow id in this
-	fn page_for_row(
+	pub(super) fn page_for_row(
 		&self,
 		row_id: u32,
 		subrow_id: u16,
-		language: Option<Language>,
-	) -> Result<Arc<exd::ExcelData>> {
+		config: &RowConfig,
+	) -> Result<(Arc<exd::ExcelData>, Language)> {
 		let header = self.header()?;
 
 		let start_id = header
@@ -183,40 +509,289 @@ impl<'i, S: SheetMetadata> Sheet<'i, S> {
 			})?
 			.start_id();
 
-		self.page(start_id, language)
+		self.page_with_language(start_id, config)
 	}
 
-	pub(super) fn page(
+	pub(super) fn page(&self, start_id: u32, config: &RowConfig) -> Result<Arc<exd::ExcelData>> {
+		let (page, _) = self.page_with_language(start_id, config)?;
+		Ok(page)
+	}
+
+	// Read in a page, alongside the language it was read in. Languages in the
+	// fallback chain are tried in order until a page is found.
+	pub(super) fn page_with_language(
 		&self,
 		start_id: u32,
-		language: Option<Language>,
-	) -> Result<Arc<exd::ExcelData>> {
-		let language = self.resolve_language(language)?;
+		config: &RowConfig,
+	) -> Result<(Arc<exd::ExcelData>, Language)> {
+		let languages = self.language_chain(config)?;
+		let name = self.name();
+
+		let load = |language| {
+			self.pages
+				.try_get_or_insert(&name, (start_id, language), || {
+					let header = self.header()?;
+					self.overlay.page(&name, &header, start_id, language, || {
+						self.ironworks.file(&path::exd(&name, start_id, language))
+					})
+				})
+				.map(|page| (page, language))
+		};
 
-		// Try to read in the page for the requested (sub)row.
-		self.cache
-			.pages
-			.try_get_or_insert((start_id, language), || {
-				let path = path::exd(&self.name(), start_id, language);
-				self.ironworks.file(&path)
-			})
+		// The chain is never empty, see language_chain.
+		let (&last, rest) = languages.split_last().unwrap();
+		for &language in rest {
+			match load(language) {
+				// Partially localised data may be missing pages for a language listed by
+				// the header, fall through to the next language.
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => return other,
+			}
+		}
+
+		load(last)
 	}
 
-	fn resolve_language(&self, language: Option<Language>) -> Result<Language> {
+	// Get the language that would be read first for the provided config.
+	// Build the ordered list of languages supported by this sheet that should be
+	// tried when reading with the provided config. Fails if there are none.
+	fn language_chain(&self, config: &RowConfig) -> Result<Vec<Language>> {
 		let header = self.header()?;
 
-		// Get the language to load, or NONE if the language is not supported by this sheet.
-		// TODO: Should an explicit language request fail hard on miss?
-		let requested_language = language.unwrap_or(self.default_language);
-		[requested_language, Language::None]
-			.into_iter()
-			.find(|&language| header.languages().contains(&language.into()))
-			// TODO: Should this be Invalid or NotFound?
+		let requested_language = config.language.unwrap_or(self.default_language);
+		let fallback = match config.strict.unwrap_or(self.default_strict) {
+			true => &[][..],
+			false => config.fallback.as_deref().unwrap_or(&self.default_fallback),
+		};
+
+		let mut languages = Vec::new();
+		for &language in [requested_language].iter().chain(fallback) {
+			if header.languages().contains(&language.into()) && !languages.contains(&language) {
+				languages.push(language);
+			}
+		}
+
+		if languages.is_empty() {
 			// TODO: Should we have an explicit ErrorValue for language?
-			.ok_or_else(|| {
-				Error::NotFound(ErrorValue::Other(format!(
-					"language {requested_language:?}"
-				)))
-			})
+			return Err(Error::NotFound(ErrorValue::Other(format!(
+				"language {requested_language:?}"
+			))));
+		}
+
+		Ok(languages)
+	}
+}
+
+impl<'i, S: SheetMetadata> IntoIterator for Sheet<'i, S> {
+	type Item = S::Row;
+	type IntoIter = SheetIterator<'i, S>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		SheetIterator::new(self, Default::default())
+	}
+}
+
+impl<'i, S: SheetMetadata> IntoIterator for &Sheet<'i, S> {
+	type Item = S::Row;
+	type IntoIter = SheetIterator<'i, S>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+// Fetch the raw data for a (sub)row from its page.
+pub(super) fn row_data<'p>(
+	page: &'p exd::ExcelData,
+	header: &exh::ExcelHeader,
+	row_id: u32,
+	subrow_id: u16,
+) -> Result<&'p [u8]> {
+	Ok(&page.bytes()[row_range(page, header, row_id, subrow_id)?])
+}
+
+// Fetch the IDs of the subrows of a row from its page. Sheets without subrows
+// only contain subrow 0.
+pub(super) fn subrow_ids(
+	page: &exd::ExcelData,
+	header: &exh::ExcelHeader,
+	row_id: u32,
+) -> Result<Vec<u16>> {
+	match header.kind() {
+		exh::SheetKind::Subrows => page.subrow_ids(row_id),
+		_ => Ok(vec![0]),
+	}
+}
+
+// Fetch the range of a (sub)row's raw data within its page.
+fn row_range(
+	page: &exd::ExcelData,
+	header: &exh::ExcelHeader,
+	row_id: u32,
+	subrow_id: u16,
+) -> Result<Range<usize>> {
+	match header.kind() {
+		exh::SheetKind::Subrows => page.subrow_range(row_id, subrow_id),
+		_ => page.row_range(row_id),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::collections::HashMap;
+
+	use crate::{
+		excel::{fixture::Fixture, Excel, Language, Row},
+		file::{
+			exd::ExcelData,
+			exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		},
+		Ironworks,
+	};
+
+	// A sheet with English and Japanese data, with Korean listed in the header
+	// but missing its data page.
+	fn ironworks() -> Ironworks {
+		let mut japanese = ExcelData::new();
+		japanese.set_row(0, 2u32.to_be_bytes().to_vec()).unwrap();
+
+		let mut fixture = Fixture::new();
+		fixture
+			.sheet(
+				"Sheet",
+				SheetKind::Default,
+				&[Language::English, Language::Japanese],
+				&[(0, &[(0, &[1])])],
+			)
+			.header(
+				"Sheet",
+				&ExcelHeader::new(
+					SheetKind::Default,
+					4,
+					vec![ColumnDefinition::new(ColumnKind::UInt32, 0)],
+					vec![PageDefinition::new(0, 1)],
+					1,
+					vec![
+						Language::English.into(),
+						Language::Japanese.into(),
+						Language::Korean.into(),
+					],
+				),
+			)
+			.page("Sheet", 0, Language::Japanese, &japanese);
+		fixture.build()
+	}
+
+	fn values(rows: HashMap<Language, Row>) -> HashMap<Language, u32> {
+		rows.into_iter()
+			.map(|(language, row)| (language, *row.field(0).unwrap().as_u32().unwrap()))
+			.collect()
+	}
+
+	#[test]
+	fn fallback() {
+		let ironworks = ironworks();
+
+		// The default fallback of None is not available.
+		let excel = Excel::with().language(Language::German).build(&ironworks);
+		assert!(excel.sheet("Sheet").unwrap().row(0).is_err());
+
+		let excel = Excel::with()
+			.language(Language::German)
+			.fallback([Language::Japanese, Language::English])
+			.build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+		assert_eq!(sheet.row(0).unwrap().language(), Language::Japanese);
+
+		let row = sheet.with().language(Language::English).row(0).unwrap();
+		assert_eq!(row.language(), Language::English);
+
+		let row = sheet.with().fallback([Language::English]).row(0).unwrap();
+		assert_eq!(row.language(), Language::English);
+	}
+
+	#[test]
+	fn missing_page() {
+		let ironworks = ironworks();
+		let excel = Excel::with()
+			.language(Language::Korean)
+			.fallback([Language::English])
+			.build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		assert_eq!(sheet.row(0).unwrap().language(), Language::English);
+		let languages = sheet.iter().map(|row| row.language()).collect::<Vec<_>>();
+		assert_eq!(languages, vec![Language::English]);
+	}
+
+	#[test]
+	fn strict() {
+		let ironworks = ironworks();
+		let excel = Excel::with()
+			.language(Language::German)
+			.fallback([Language::English])
+			.strict(true)
+			.build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		assert!(sheet.row(0).is_err());
+		assert!(sheet.with().strict(false).row(0).is_ok());
+		let row = sheet.with().language(Language::Japanese).row(0).unwrap();
+		assert_eq!(row.language(), Language::Japanese);
+
+		// Missing pages do not fall back in strict mode.
+		assert!(sheet.with().language(Language::Korean).row(0).is_err());
+	}
+
+	#[test]
+	fn all_languages() {
+		let ironworks = ironworks();
+		let excel = Excel::with().strict(true).build(&ironworks);
+		let sheet = excel.sheet("Sheet").unwrap();
+
+		// Korean is listed by the header, but has no data.
+		let expected = HashMap::from([(Language::English, 1), (Language::Japanese, 2)]);
+		assert_eq!(values(sheet.row_all_languages(0).unwrap()), expected);
+		assert!(sheet.row_all_languages(1).is_err());
+
+		let rows = sheet
+			.iter_all_languages()
+			.map(|rows| values(rows.unwrap()))
+			.collect::<Vec<_>>();
+		assert_eq!(rows, vec![expected]);
+	}
+
+	#[test]
+	fn metadata() {
+		let mut fixture = Fixture::new();
+		fixture.sheet(
+			"Subrows",
+			SheetKind::Subrows,
+			&[Language::None],
+			&[(0, &[(0, &[1, 2]), (2, &[3])]), (5, &[(5, &[4, 5, 6])])],
+		);
+		let ironworks = fixture.build();
+		let excel = Excel::new(&ironworks);
+		let sheet = excel.sheet("Subrows").unwrap();
+
+		assert_eq!(sheet.len().unwrap(), 3);
+		assert_eq!(sheet.row_ids().unwrap(), vec![0, 2, 5]);
+		assert_eq!(
+			sheet.len().unwrap() as usize,
+			sheet.row_ids().unwrap().len()
+		);
+		assert!(sheet.contains(2).unwrap());
+		assert!(!sheet.contains(1).unwrap());
+		assert!(!sheet.contains(10).unwrap());
+		assert_eq!(sheet.subrow_count(5).unwrap(), 3);
+		assert!(sheet.subrow_count(1).is_err());
+
+		let pages = sheet
+			.pages()
+			.unwrap()
+			.into_iter()
+			.map(|page| (page.definition().start_id(), page.row_count()))
+			.collect::<Vec<_>>();
+		assert_eq!(pages, vec![(0, 2), (5, 1)]);
 	}
 }
@@ -0,0 +1,186 @@
+use crate::file::exh;
+
+/// Signature of the column layout of a sheet.
+///
+/// Signatures can be stored alongside code or queries that read columns by
+/// index, and later compared against the live layout of the sheet with
+/// [`ColumnSignature::map_to`], such that indices can be remapped after columns
+/// are inserted or removed by a game update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnSignature {
+	columns: Vec<exh::ColumnDefinition>,
+}
+
+impl ColumnSignature {
+	/// Create a signature from the column definitions of a sheet.
+	pub fn new(columns: Vec<exh::ColumnDefinition>) -> Self {
+		Self { columns }
+	}
+
+	/// Column definitions forming this signature.
+	pub fn columns(&self) -> &[exh::ColumnDefinition] {
+		&self.columns
+	}
+
+	/// Map the columns of this signature to the columns of `live`.
+	///
+	/// Columns are aligned using a longest common subsequence of the two layouts.
+	/// Columns may only match columns of the same kind, with matches that also
+	/// share an offset preferred, such that columns shifted by an insertion or
+	/// removal are still matched.
+	pub fn map_to(&self, live: &ColumnSignature) -> ColumnMapping {
+		let (a, b) = (&self.columns, &live.columns);
+
+		// Matching offsets only break ties between alignments with an equal number
+		// of matched columns, as the bonus across all matches never exceeds the
+		// score of a single match.
+		let weight = u32::try_from(a.len().min(b.len())).unwrap() + 1;
+		let score = |a: &exh::ColumnDefinition, b: &exh::ColumnDefinition| match (
+			a.kind() == b.kind(),
+			a.offset() == b.offset(),
+		) {
+			(true, true) => weight + 1,
+			(true, false) => weight,
+			(false, _) => 0,
+		};
+
+		// Build a table of the best total score for each pair of prefixes.
+		let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+		for i in 1..=a.len() {
+			for j in 1..=b.len() {
+				let matched = match score(&a[i - 1], &b[j - 1]) {
+					0 => 0,
+					score => table[i - 1][j - 1] + score,
+				};
+				table[i][j] = matched.max(table[i - 1][j]).max(table[i][j - 1]);
+			}
+		}
+
+		// Backtrack through the table to pair up matched columns.
+		let mut indices = vec![None; a.len()];
+		let (mut i, mut j) = (a.len(), b.len());
+		while i > 0 && j > 0 {
+			let score = score(&a[i - 1], &b[j - 1]);
+			if score > 0 && table[i][j] == table[i - 1][j - 1] + score {
+				indices[i - 1] = Some(j - 1);
+				i -= 1;
+				j -= 1;
+			} else if table[i - 1][j] >= table[i][j - 1] {
+				i -= 1;
+			} else {
+				j -= 1;
+			}
+		}
+
+		let mut matched = vec![false; b.len()];
+		for &index in indices.iter().flatten() {
+			matched[index] = true;
+		}
+
+		ColumnMapping {
+			removed: (0..a.len()).filter(|&i| indices[i].is_none()).collect(),
+			inserted: (0..b.len()).filter(|&j| !matched[j]).collect(),
+			indices,
+		}
+	}
+}
+
+/// Mapping of column indices from a stored [`ColumnSignature`] to the live
+/// column layout of a sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+	indices: Vec<Option<usize>>,
+	inserted: Vec<usize>,
+	removed: Vec<usize>,
+}
+
+impl ColumnMapping {
+	/// Get the index within the live layout of the column at `index` within the
+	/// stored layout. Returns `None` if the column has been removed.
+	pub fn get(&self, index: usize) -> Option<usize> {
+		self.indices.get(index).copied().flatten()
+	}
+
+	/// Indices of columns within the live layout that are not present in the
+	/// stored layout.
+	pub fn inserted(&self) -> &[usize] {
+		&self.inserted
+	}
+
+	/// Indices of columns within the stored layout that are not present in the
+	/// live layout.
+	pub fn removed(&self) -> &[usize] {
+		&self.removed
+	}
+
+	/// Check if every stored column maps to the same index in the live layout,
+	/// with no columns inserted or removed.
+	pub fn is_identity(&self) -> bool {
+		self.inserted.is_empty()
+			&& self
+				.indices
+				.iter()
+				.enumerate()
+				.all(|(index, mapped)| *mapped == Some(index))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::file::exh::{ColumnDefinition, ColumnKind};
+
+	use super::ColumnSignature;
+
+	fn signature(columns: &[(ColumnKind, u16)]) -> ColumnSignature {
+		ColumnSignature::new(
+			columns
+				.iter()
+				.map(|&(kind, offset)| ColumnDefinition::new(kind, offset))
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn identity() {
+		let stored = signature(&[(ColumnKind::String, 0), (ColumnKind::UInt8, 4)]);
+		let mapping = stored.map_to(&stored.clone());
+		assert!(mapping.is_identity());
+		assert_eq!(mapping.get(1), Some(1));
+	}
+
+	#[test]
+	fn inserted() {
+		use ColumnKind as K;
+		let stored = signature(&[(K::String, 0), (K::UInt32, 4), (K::UInt32, 8)]);
+		let live = signature(&[
+			(K::String, 0),
+			(K::String, 4),
+			(K::UInt32, 8),
+			(K::UInt32, 12),
+		]);
+
+		let mapping = stored.map_to(&live);
+		assert!(!mapping.is_identity());
+		assert_eq!(mapping.get(0), Some(0));
+		assert_eq!(mapping.get(1), Some(2));
+		assert_eq!(mapping.get(2), Some(3));
+		assert_eq!(mapping.inserted(), [1]);
+		assert!(mapping.removed().is_empty());
+	}
+
+	#[test]
+	fn removed() {
+		use ColumnKind as K;
+		let stored = signature(&[(K::UInt8, 0), (K::UInt8, 1), (K::Int16, 2)]);
+		let live = signature(&[(K::UInt8, 1), (K::Int16, 2)]);
+
+		// The remaining UInt8 column retained its offset, and is preferred.
+		let mapping = stored.map_to(&live);
+		assert_eq!(mapping.get(0), None);
+		assert_eq!(mapping.get(1), Some(0));
+		assert_eq!(mapping.get(2), Some(1));
+		assert_eq!(mapping.removed(), [0]);
+		assert!(mapping.inserted().is_empty());
+	}
+}
@@ -0,0 +1,338 @@
+use std::{collections::HashMap, io::Cursor, sync::Arc};
+
+use binrw::BinReaderExt;
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::exh,
+	sestring::SeString,
+};
+
+use super::{
+	field::Field,
+	row::{ColumnSpecifier, Row},
+};
+
+/// Writer for the binary representation of a single Excel (sub)row. Built rows
+/// can be inserted into data pages with [`ExcelData::set_row`] or
+/// [`ExcelData::set_subrow`].
+///
+/// [`ExcelData::set_row`]: crate::file::exd::ExcelData::set_row
+/// [`ExcelData::set_subrow`]: crate::file::exd::ExcelData::set_subrow
+#[derive(Debug)]
+pub struct RowWriter {
+	header: Arc<exh::ExcelHeader>,
+	structured: Vec<u8>,
+
+	// Strings keyed by the offset of their column, alongside the order they
+	// should be written to the string data.
+	strings: HashMap<u16, SeString>,
+	string_order: Vec<u16>,
+
+	// String data of the source row. This is retained until a string is modified,
+	// such that unmodified rows can be written back out byte-for-byte.
+	string_data: Option<Vec<u8>>,
+}
+
+impl RowWriter {
+	/// Create a writer for a new row in the sheet described by `header`. All
+	/// fields default to zero or empty values.
+	pub fn new(header: Arc<exh::ExcelHeader>) -> Self {
+		let mut string_order = string_columns(&header)
+			.map(|column| column.offset())
+			.collect::<Vec<_>>();
+		string_order.sort_unstable();
+
+		Self {
+			structured: vec![0; header.row_size().into()],
+			strings: string_order
+				.iter()
+				.map(|&offset| (offset, SeString::default()))
+				.collect(),
+			string_order,
+			string_data: None,
+			header,
+		}
+	}
+
+	/// Create a writer initialised with the fields of an existing row.
+	pub fn from_row(row: &Row) -> Result<Self> {
+		let header = row.header().clone();
+		let mut data = row.data().to_vec();
+
+		let row_size = usize::from(header.row_size());
+		if data.len() < row_size {
+			return Err(Error::Invalid(
+				row_value(row),
+				format!("row data is smaller than row size {row_size}"),
+			));
+		}
+		let string_data = data.split_off(row_size);
+
+		let mut strings = Vec::new();
+		for column in string_columns(&header) {
+			let string_offset = read_u32(&data, column.offset())
+				.ok_or_else(|| Error::Invalid(row_value(row), "column out of bounds".into()))?;
+			let mut cursor = Cursor::new(&string_data);
+			cursor.set_position(string_offset.into());
+			let string = cursor.read_be::<SeString>()?;
+			strings.push((string_offset, column.offset(), string));
+		}
+
+		// Retain the original order of the strings within the string data.
+		strings.sort_by_key(|&(string_offset, column_offset, _)| (string_offset, column_offset));
+
+		Ok(Self {
+			header,
+			structured: data,
+			string_order: strings.iter().map(|&(_, offset, _)| offset).collect(),
+			strings: strings
+				.into_iter()
+				.map(|(_, offset, string)| (offset, string))
+				.collect(),
+			string_data: Some(string_data),
+		})
+	}
+
+	/// Set the value of the field at the specified column. The field type must
+	/// match the kind of the column.
+	pub fn set_field<'a>(
+		&mut self,
+		specifier: impl Into<ColumnSpecifier<'a>>,
+		field: Field,
+	) -> Result<&mut Self> {
+		let column = match specifier.into() {
+			ColumnSpecifier::Definition(definition) => definition.clone(),
+			ColumnSpecifier::Index(index) => self
+				.header
+				.columns()
+				.get(index)
+				.cloned()
+				.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Column {index}"))))?,
+		};
+
+		use exh::ColumnKind as K;
+		use Field as F;
+
+		let kind = column.kind();
+		let offset = column.offset();
+
+		match (kind, field) {
+			(K::String, F::String(value)) => {
+				if !self.strings.contains_key(&offset) {
+					return Err(column_error(offset, "column is not a string column"));
+				}
+				self.strings.insert(offset, value);
+				self.string_data = None;
+			}
+
+			(K::Bool, F::Bool(value)) => self.write(offset, &[u8::from(value)])?,
+			(
+				K::PackedBool0
+				| K::PackedBool1
+				| K::PackedBool2
+				| K::PackedBool3
+				| K::PackedBool4
+				| K::PackedBool5
+				| K::PackedBool6
+				| K::PackedBool7,
+				F::Bool(value),
+			) => {
+				let mask = 1 << (u16::from(kind) - u16::from(K::PackedBool0));
+				let byte = self
+					.structured
+					.get_mut(usize::from(offset))
+					.ok_or_else(|| column_error(offset, "column out of bounds"))?;
+				match value {
+					true => *byte |= mask,
+					false => *byte &= !mask,
+				}
+			}
+
+			(K::Int8, F::I8(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::Int16, F::I16(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::Int32, F::I32(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::Int64, F::I64(value)) => self.write(offset, &value.to_be_bytes())?,
+
+			(K::UInt8, F::U8(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::UInt16, F::U16(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::UInt32, F::U32(value)) => self.write(offset, &value.to_be_bytes())?,
+			(K::UInt64, F::U64(value)) => self.write(offset, &value.to_be_bytes())?,
+
+			(K::Float32, F::F32(value)) => self.write(offset, &value.to_be_bytes())?,
+
+			(kind, field) => {
+				return Err(column_error(
+					offset,
+					&format!("cannot write {field:?} to {kind:?} column"),
+				))
+			}
+		}
+
+		Ok(self)
+	}
+
+	/// Build the binary representation of the row. For sheets with subrows, this
+	/// is the structured data of the subrow alone.
+	pub fn build(&self) -> Vec<u8> {
+		let mut data = self.structured.clone();
+
+		if let Some(string_data) = &self.string_data {
+			data.extend_from_slice(string_data);
+			return data;
+		}
+
+		// Strings are written sequentially, each followed by a null terminator.
+		let mut string_data = Vec::new();
+		for &offset in &self.string_order {
+			let string_offset = u32::try_from(string_data.len()).unwrap();
+			let start = usize::from(offset);
+			data[start..start + 4].copy_from_slice(&string_offset.to_be_bytes());
+
+			string_data.extend_from_slice(self.strings[&offset].as_bytes());
+			string_data.push(0);
+		}
+
+		// Rows containing string data are padded to a 4-byte boundary.
+		if !string_data.is_empty() {
+			data.extend_from_slice(&string_data);
+			data.resize((data.len() + 3) & !3, 0);
+		}
+
+		data
+	}
+
+	fn write(&mut self, offset: u16, bytes: &[u8]) -> Result<()> {
+		let start = usize::from(offset);
+		let target = self
+			.structured
+			.get_mut(start..start + bytes.len())
+			.ok_or_else(|| column_error(offset, "column out of bounds"))?;
+		target.copy_from_slice(bytes);
+		Ok(())
+	}
+}
+
+fn string_columns(header: &exh::ExcelHeader) -> impl Iterator<Item = &exh::ColumnDefinition> {
+	// Sheets with subrows do not support string data.
+	let supports_strings = header.kind() != exh::SheetKind::Subrows;
+	header
+		.columns()
+		.iter()
+		.filter(move |column| supports_strings && column.kind() == exh::ColumnKind::String)
+}
+
+fn read_u32(data: &[u8], offset: u16) -> Option<u32> {
+	let offset = usize::from(offset);
+	let bytes = data.get(offset..offset + 4)?;
+	Some(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+fn row_value(row: &Row) -> ErrorValue {
+	ErrorValue::Row {
+		row: row.row_id(),
+		subrow: row.subrow_id(),
+		sheet: None,
+	}
+}
+
+fn column_error(offset: u16, message: &str) -> Error {
+	Error::Invalid(
+		ErrorValue::Other(format!("Column at offset {offset}")),
+		message.into(),
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use crate::{
+		excel::{row::Row, Field, Language},
+		file::exh::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind},
+		sestring::SeString,
+	};
+
+	use super::RowWriter;
+
+	fn header() -> Arc<ExcelHeader> {
+		Arc::new(ExcelHeader::new(
+			SheetKind::Default,
+			12,
+			vec![
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::String, 4),
+				ColumnDefinition::new(ColumnKind::UInt16, 8),
+				ColumnDefinition::new(ColumnKind::PackedBool2, 10),
+			],
+			vec![PageDefinition::new(0, 1)],
+			1,
+			vec![1],
+		))
+	}
+
+	fn row(writer: &RowWriter) -> Row {
+		Row::new(0, 0, Language::None, header(), writer.build())
+	}
+
+	#[test]
+	fn write_fields() {
+		let mut writer = RowWriter::new(header());
+		writer
+			.set_field(0, Field::String(SeString::try_from("hello").unwrap()))
+			.unwrap()
+			.set_field(2, Field::U16(1234))
+			.unwrap()
+			.set_field(3, Field::Bool(true))
+			.unwrap();
+
+		let data = writer.build();
+		assert_eq!(data.len() % 4, 0);
+
+		let row = row(&writer);
+		assert_eq!(
+			row.field(0).unwrap().into_string().unwrap().to_string(),
+			"hello"
+		);
+		assert_eq!(row.field(1).unwrap().into_string().unwrap().to_string(), "");
+		assert_eq!(row.field(2).unwrap().into_u16().unwrap(), 1234);
+		assert!(row.field(3).unwrap().into_bool().unwrap());
+	}
+
+	#[test]
+	fn invalid_field() {
+		let mut writer = RowWriter::new(header());
+		writer.set_field(2, Field::U32(1)).unwrap_err();
+		writer.set_field(9, Field::U16(1)).unwrap_err();
+	}
+
+	#[test]
+	fn round_trip() {
+		let mut writer = RowWriter::new(header());
+		writer
+			.set_field(1, Field::String(SeString::try_from("second").unwrap()))
+			.unwrap()
+			.set_field(0, Field::String(SeString::try_from("first").unwrap()))
+			.unwrap();
+		let original = writer.build();
+
+		// Unmodified rows are reproduced exactly.
+		let source = row(&writer);
+		let mut writer = RowWriter::from_row(&source).unwrap();
+		assert_eq!(writer.build(), original);
+
+		// Editing a field rebuilds the string data.
+		writer
+			.set_field(1, Field::String(SeString::try_from("edited").unwrap()))
+			.unwrap();
+		let edited = Row::new(0, 0, Language::None, header(), writer.build());
+		assert_eq!(
+			edited.field(0).unwrap().into_string().unwrap().to_string(),
+			"first"
+		);
+		assert_eq!(
+			edited.field(1).unwrap().into_string().unwrap().to_string(),
+			"edited"
+		);
+	}
+}
@@ -1,8 +1,11 @@
 //! Structs and utilities for parsing .exd files.
 
-use std::io::{Cursor, Read, Seek};
+use std::{
+	io::{Cursor, Read, Seek, Write},
+	ops::Range,
+};
 
-use binrw::{binread, BinRead, BinResult, ReadOptions};
+use binrw::{binread, binrw, BinRead, BinResult, BinWrite, ReadOptions};
 use getset::{CopyGetters, Getters};
 
 use crate::{
@@ -10,29 +13,32 @@ use crate::{
 	FileStream,
 };
 
-use super::file::File;
+use super::file::{File, WriteFile};
 
 /// An Excel data page. One or more pages form the full dataset for an Excel
 /// sheet. Metadata for sheets is contained in an associated .exh Excel header file.
-#[binread]
+#[binrw]
 #[derive(Debug, Getters)]
-#[br(big, magic = b"EXDF")]
+#[brw(big, magic = b"EXDF")]
 pub struct ExcelData {
-	_version: u16,
-	// unknown1: u16,
-	#[br(pad_before = 2, temp)]
+	version: u16,
+	unknown1: u16,
+
+	#[br(temp)]
+	#[bw(calc = u32::try_from(rows.len()).unwrap() * RowDefinition::SIZE)]
 	index_size: u32,
 
-	// unknown2: [u16; 10],
+	data_size: u32,
+
+	unknown2: [u16; 8],
+
 	/// Vector of rows contained within this page.
-	#[br(
-		pad_before = 20,
-		count = index_size / RowDefinition::SIZE,
-	)]
+	#[br(count = index_size / RowDefinition::SIZE)]
 	#[get = "pub"]
 	rows: Vec<RowDefinition>,
 
 	#[br(parse_with = current_position)]
+	#[bw(ignore)]
 	data_offset: u64,
 
 	#[br(parse_with = until_eof)]
@@ -40,19 +46,187 @@ pub struct ExcelData {
 }
 
 impl ExcelData {
+	const VERSION: u16 = 2;
+	const HEADER_SIZE: u32 = 0x20;
+
+	/// Build a new, empty data page.
+	pub fn new() -> Self {
+		Self {
+			version: Self::VERSION,
+			unknown1: 0,
+			data_size: 0,
+			unknown2: [0; 8],
+			rows: Vec::new(),
+			data_offset: Self::HEADER_SIZE.into(),
+			data: Vec::new(),
+		}
+	}
+
+	/// Insert or replace the data associated with the specified row. For sheets
+	/// without subrows, `data` should contain the structured row data, followed
+	/// by any string data referenced by the row.
+	pub fn set_row(&mut self, row_id: u32, data: Vec<u8>) -> Result<()> {
+		let mut rows = self.row_buffers()?;
+		let row = RowBuffer {
+			id: row_id,
+			row_count: 1,
+			data,
+		};
+		match rows.binary_search_by_key(&row_id, |row| row.id) {
+			Ok(index) => rows[index] = row,
+			Err(index) => rows.insert(index, row),
+		}
+		self.rebuild(rows);
+		Ok(())
+	}
+
+	/// Insert or replace the data associated with the specified subrow. `data`
+	/// should contain the structured subrow data. Subrows are stored in the order
+	/// they were first inserted.
+	pub fn set_subrow(&mut self, row_id: u32, subrow_id: u16, data: Vec<u8>) -> Result<()> {
+		let mut rows = self.row_buffers()?;
+		let index = match rows.binary_search_by_key(&row_id, |row| row.id) {
+			Ok(index) => index,
+			Err(index) => {
+				rows.insert(
+					index,
+					RowBuffer {
+						id: row_id,
+						row_count: 0,
+						data: Vec::new(),
+					},
+				);
+				index
+			}
+		};
+
+		let row = &mut rows[index];
+		let subrow_size = SubrowHeader::SIZE + data.len();
+
+		// Look for an existing subrow with the requested ID to replace.
+		let existing = (0..usize::from(row.row_count))
+			.map(|index| index * subrow_size)
+			.find(|&offset| {
+				row.data.get(offset..offset + SubrowHeader::SIZE) == Some(&subrow_id.to_be_bytes())
+			});
+
+		if row.row_count > 0 && row.data.len() != subrow_size * usize::from(row.row_count) {
+			return Err(Error::Invalid(
+				ErrorValue::Row {
+					row: row_id,
+					subrow: subrow_id,
+					sheet: None,
+				},
+				format!(
+					"subrow data size {} does not match existing subrows",
+					data.len()
+				),
+			));
+		}
+
+		match existing {
+			Some(offset) => {
+				row.data[offset + SubrowHeader::SIZE..offset + subrow_size].copy_from_slice(&data)
+			}
+			None => {
+				row.data.extend_from_slice(&subrow_id.to_be_bytes());
+				row.data.extend_from_slice(&data);
+				row.row_count += 1;
+			}
+		}
+
+		self.rebuild(rows);
+		Ok(())
+	}
+
+	/// Remove the specified row, along with any subrows it contains, returning
+	/// `true` if the row was present.
+	pub fn remove_row(&mut self, row_id: u32) -> Result<bool> {
+		let mut rows = self.row_buffers()?;
+		let Ok(index) = rows.binary_search_by_key(&row_id, |row| row.id) else {
+			return Ok(false);
+		};
+		rows.remove(index);
+		self.rebuild(rows);
+		Ok(true)
+	}
+
+	fn row_buffers(&self) -> Result<Vec<RowBuffer>> {
+		let mut rows = self
+			.rows
+			.iter()
+			.map(|definition| {
+				let (header, offset) = self.row_meta(definition.id)?;
+				let length = usize::try_from(header.data_size).unwrap();
+				Ok(RowBuffer {
+					id: definition.id,
+					row_count: header.row_count,
+					data: self.data[offset..offset + length].to_vec(),
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+		rows.sort_by_key(|row| row.id);
+		Ok(rows)
+	}
+
+	// Lay out the provided rows sequentially, immediately following the row index.
+	fn rebuild(&mut self, rows: Vec<RowBuffer>) {
+		let index_size = u32::try_from(rows.len()).unwrap() * RowDefinition::SIZE;
+		self.data_offset = (Self::HEADER_SIZE + index_size).into();
+
+		self.rows.clear();
+		self.data.clear();
+		for row in rows {
+			let offset = self.data_offset + u64::try_from(self.data.len()).unwrap();
+			self.rows.push(RowDefinition {
+				id: row.id,
+				offset: offset.try_into().unwrap(),
+			});
+
+			self.data
+				.extend_from_slice(&u32::try_from(row.data.len()).unwrap().to_be_bytes());
+			self.data.extend_from_slice(&row.row_count.to_be_bytes());
+			self.data.extend_from_slice(&row.data);
+		}
+
+		self.data_size = self.data.len().try_into().unwrap();
+	}
+
 	/// Fetch the slice of data associated with the specified row. If this data
 	/// page is for a sheet with subrows, this will include all child rows of the
 	/// specified row. Otherwise, it will contain the row and any trailing string data.
 	pub fn row_data(&self, row_id: u32) -> Result<&[u8]> {
+		Ok(&self.data[self.row_range(row_id)?])
+	}
+
+	/// Fetch the slice of data associated with the specified subrow.
+	pub fn subrow_data(&self, row_id: u32, subrow_id: u16) -> Result<&[u8]> {
+		Ok(&self.data[self.subrow_range(row_id, subrow_id)?])
+	}
+
+	/// Number of subrows contained within the specified row. Rows in sheets
+	/// without subrows contain a single subrow.
+	pub fn subrow_count(&self, row_id: u32) -> Result<u16> {
+		let (row_header, _) = self.row_meta(row_id)?;
+		Ok(row_header.row_count)
+	}
+
+	// Raw data of this page, indexable with row and subrow ranges.
+	pub(crate) fn bytes(&self) -> &[u8] {
+		&self.data
+	}
+
+	// Range of data associated with the specified row. See `row_data`.
+	pub(crate) fn row_range(&self, row_id: u32) -> Result<Range<usize>> {
 		let (row_header, offset) = self.row_meta(row_id)?;
 
-		// Get a slice of the row's data
+		// Get the range of the row's data
 		let length: usize = row_header.data_size.try_into().unwrap();
-		Ok(&self.data[offset..offset + length])
+		Ok(offset..offset + length)
 	}
 
-	/// Fetch the slice of data associated with the specified subrow.
-	pub fn subrow_data(&self, row_id: u32, subrow_id: u16) -> Result<&[u8]> {
+	// Range of data associated with the specified subrow. See `subrow_data`.
+	pub(crate) fn subrow_range(&self, row_id: u32, subrow_id: u16) -> Result<Range<usize>> {
 		let (row_header, offset) = self.row_meta(row_id)?;
 
 		// Subrows invariably do not support unstructured data (i.e. strings), and
@@ -93,12 +267,12 @@ impl ExcelData {
 			Some(Ok(subrow_offset)) => subrow_offset,
 		};
 
-		// Get the slice of subrow data.
-		Ok(&self.data[subrow_offset + SubrowHeader::SIZE..subrow_offset + subrow_size])
+		// Get the range of subrow data.
+		Ok(subrow_offset + SubrowHeader::SIZE..subrow_offset + subrow_size)
 	}
 
-	// TODO: This is a hacky implementation for use in excel's sheet iterator. Remove once iterator is rewritten to be less insane.
-	pub(crate) fn subrow_max(&self, row_id: u32) -> Result<u16> {
+	// IDs of the subrows contained within the specified row, in page order.
+	pub(crate) fn subrow_ids(&self, row_id: u32) -> Result<Vec<u16>> {
 		let (row_header, offset) = self.row_meta(row_id)?;
 
 		let subrow_size =
@@ -106,14 +280,12 @@ impl ExcelData {
 
 		let mut cursor = Cursor::new(&self.data);
 		(0..row_header.row_count)
-			.map(move |index| -> Result<_> {
-				let subrow_offset = offset + subrow_size * usize::try_from(index).unwrap();
+			.map(|index| -> Result<_> {
+				let subrow_offset = offset + subrow_size * usize::from(index);
 				cursor.set_position(subrow_offset.try_into().unwrap());
-				Ok(SubrowHeader::read(&mut cursor)?)
-			})
-			.fold(Ok(0), |a, b| {
-				a.and_then(|a| b.map(|b| std::cmp::max(a, b.id)))
+				Ok(SubrowHeader::read(&mut cursor)?.id)
 			})
+			.collect()
 	}
 
 	fn row_meta(&self, row_id: u32) -> Result<(RowHeader, usize)> {
@@ -122,7 +294,7 @@ impl ExcelData {
 			// In all likelihood the row can be found simply by indexing
 			// the vector based on the ID's offset from the first row.
 			let first_row_id = self.rows.get(0).map_or(0, |row| row.id);
-			let row_idx = (row_id - first_row_id) as usize;
+			let row_idx = row_id.wrapping_sub(first_row_id) as usize;
 			if row_idx < self.rows.len() && self.rows[row_idx].id == row_id {
 				&self.rows[row_idx]
 			} else {
@@ -147,16 +319,28 @@ impl ExcelData {
 	}
 }
 
+impl Default for ExcelData {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl File for ExcelData {
 	fn read(mut stream: impl FileStream) -> Result<Self> {
 		Ok(<Self as BinRead>::read(&mut stream)?)
 	}
 }
 
+impl WriteFile for ExcelData {
+	fn write(&self, mut stream: impl Write + Seek) -> Result<()> {
+		Ok(BinWrite::write(self, &mut stream)?)
+	}
+}
+
 /// Metadata of a row contained in a page.
-#[binread]
+#[binrw]
 #[derive(Debug, CopyGetters)]
-#[br(big)]
+#[brw(big)]
 pub struct RowDefinition {
 	/// Primary key ID of this row.
 	#[get_copy = "pub"]
@@ -186,6 +370,13 @@ struct RowHeader {
 	row_count: u16,
 }
 
+#[derive(Debug)]
+struct RowBuffer {
+	id: u32,
+	row_count: u16,
+	data: Vec<u8>,
+}
+
 #[binread]
 #[derive(Debug)]
 #[br(big)]
@@ -196,3 +387,62 @@ struct SubrowHeader {
 impl SubrowHeader {
 	const SIZE: usize = 2;
 }
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use crate::file::{File, WriteFile};
+
+	use super::ExcelData;
+
+	fn write(page: &ExcelData) -> Vec<u8> {
+		let mut cursor = Cursor::new(Vec::new());
+		page.write(&mut cursor).unwrap();
+		cursor.into_inner()
+	}
+
+	#[test]
+	fn round_trip() {
+		let mut page = ExcelData::new();
+		page.set_row(2, vec![5, 6, 7, 8]).unwrap();
+		page.set_row(1, vec![1, 2, 3, 4]).unwrap();
+
+		let bytes = write(&page);
+		let read = ExcelData::read(Cursor::new(bytes.clone())).unwrap();
+		assert_eq!(read.row_data(1).unwrap(), [1, 2, 3, 4]);
+		assert_eq!(read.row_data(2).unwrap(), [5, 6, 7, 8]);
+		assert_eq!(write(&read), bytes);
+	}
+
+	#[test]
+	fn replace_row() {
+		let mut page = ExcelData::new();
+		page.set_row(1, vec![1, 2, 3, 4]).unwrap();
+		page.set_row(2, vec![5, 6, 7, 8]).unwrap();
+		page.set_row(1, vec![9, 9]).unwrap();
+
+		let read = ExcelData::read(Cursor::new(write(&page))).unwrap();
+		assert_eq!(read.row_data(1).unwrap(), [9, 9]);
+		assert_eq!(read.row_data(2).unwrap(), [5, 6, 7, 8]);
+
+		page.remove_row(1).unwrap();
+		assert!(page.row_data(1).is_err());
+		assert_eq!(page.row_data(2).unwrap(), [5, 6, 7, 8]);
+	}
+
+	#[test]
+	fn subrows() {
+		let mut page = ExcelData::new();
+		page.set_subrow(1, 0, vec![1, 2]).unwrap();
+		page.set_subrow(1, 3, vec![3, 4]).unwrap();
+		page.set_subrow(1, 0, vec![5, 6]).unwrap();
+		page.set_subrow(1, 1, vec![7]).unwrap_err();
+
+		let read = ExcelData::read(Cursor::new(write(&page))).unwrap();
+		assert_eq!(read.subrow_data(1, 0).unwrap(), [5, 6]);
+		assert_eq!(read.subrow_data(1, 3).unwrap(), [3, 4]);
+		assert_eq!(read.subrow_ids(1).unwrap(), [0, 3]);
+		assert_eq!(read.subrow_count(1).unwrap(), 2);
+	}
+}
@@ -1,51 +1,57 @@
 //! Structs and utilities for parsing .exh files.
 
-use std::collections::HashSet;
+use std::{
+	collections::HashSet,
+	io::{Seek, Write},
+};
 
-use binrw::{binread, BinRead};
+use binrw::{binrw, BinRead, BinWrite};
 use getset::{CopyGetters, Getters};
 use num_enum::IntoPrimitive;
 
 use crate::{error::Result, FileStream};
 
-use super::File;
+use super::{File, WriteFile};
 
 /// An Excel header file, containing metadata for all associated .exd Excel data files.
-#[binread]
+#[binrw]
 #[derive(Debug, Getters, CopyGetters)]
-#[br(big, magic = b"EXHF")]
+#[brw(big, magic = b"EXHF")]
 pub struct ExcelHeader {
-	_version: u16,
+	version: u16,
 
 	/// Size of structured data in each row, in bytes.
 	#[get_copy = "pub"]
 	row_size: u16,
 
 	#[br(temp)]
+	#[bw(calc = columns.len().try_into().unwrap())]
 	column_count: u16,
 	#[br(temp)]
+	#[bw(calc = pages.len().try_into().unwrap())]
 	page_count: u16,
 	#[br(temp)]
+	#[bw(calc = language_definitions.len().try_into().unwrap())]
 	language_count: u16,
 
-	// unknown1: u16,
-	// unknown2: u8,
+	unknown1: u16,
+	unknown2: u8,
+
 	/// The kind of the relevant sheet. This value dictates the binary layout and
 	/// capabilities of rows.
-	#[br(pad_before = 3)]
 	#[get_copy = "pub"]
 	kind: SheetKind,
 
-	// unknown3: u16,
-	#[br(pad_before = 2)]
-	_row_count: u32,
+	unknown3: u16,
+
+	/// Total number of rows in the relevant sheet.
+	#[get_copy = "pub"]
+	row_count: u32,
+
+	unknown4: [u32; 2],
 
-	// unknown4: [u32; 2],
 	/// Column definitions for rows in this sheet.
-	#[br(
-		pad_before = 8,
-		count = column_count,
-	)]
+	#[br(count = column_count)]
 	#[get = "pub"]
 	columns: Vec<ColumnDefinition>,
 
@@ -54,25 +60,72 @@ pub struct ExcelHeader {
 	#[get = "pub"]
 	pages: Vec<PageDefinition>,
 
+	#[br(count = language_count)]
+	language_definitions: Vec<LanguageDefinition>,
+
 	/// Language IDs supported by this sheet.
-	#[br(
-		count = language_count,
-		map = LanguageDefinition::to_set,
-	)]
+	#[br(calc = LanguageDefinition::to_set(&language_definitions))]
+	#[bw(ignore)]
 	#[get = "pub"]
 	languages: HashSet<u8>,
 }
 
+impl ExcelHeader {
+	const VERSION: u16 = 3;
+
+	/// Build a new header for a sheet of the given kind. Rows will be `row_size`
+	/// bytes in size, with string data (if any) following the structured row data.
+	/// `row_count` is the number of rows present across all pages, which may be
+	/// less than the ID span of the pages if row IDs are not contiguous.
+	pub fn new(
+		kind: SheetKind,
+		row_size: u16,
+		columns: Vec<ColumnDefinition>,
+		pages: Vec<PageDefinition>,
+		row_count: u32,
+		languages: Vec<u8>,
+	) -> Self {
+		let language_definitions = languages
+			.into_iter()
+			.map(|language| LanguageDefinition {
+				language,
+				unknown1: 0,
+			})
+			.collect::<Vec<_>>();
+
+		Self {
+			version: Self::VERSION,
+			row_size,
+			unknown1: 0,
+			unknown2: 0,
+			kind,
+			unknown3: 0,
+			row_count,
+			unknown4: [0; 2],
+			columns,
+			pages,
+			languages: LanguageDefinition::to_set(&language_definitions),
+			language_definitions,
+		}
+	}
+}
+
 impl File for ExcelHeader {
 	fn read(mut stream: impl FileStream) -> Result<Self> {
 		Ok(<Self as BinRead>::read(&mut stream)?)
 	}
 }
 
+impl WriteFile for ExcelHeader {
+	fn write(&self, mut stream: impl Write + Seek) -> Result<()> {
+		Ok(BinWrite::write(self, &mut stream)?)
+	}
+}
+
 /// The kind of sheet.
-#[binread]
+#[binrw]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-#[br(repr = u8)]
+#[brw(repr = u8)]
 pub enum SheetKind {
 	/// Unknown kind. Will be treated equivalently to Default.
 	Unknown = 0,
@@ -87,9 +140,10 @@ pub enum SheetKind {
 }
 
 /// Metadata for a single sheet column.
-#[binread]
-#[derive(Clone, Debug, Hash, CopyGetters)]
-#[br(big)]
+#[binrw]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, CopyGetters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[brw(big)]
 pub struct ColumnDefinition {
 	/// The kind of data stored in this column.
 	#[get_copy = "pub"]
@@ -100,11 +154,20 @@ pub struct ColumnDefinition {
 	offset: u16,
 }
 
+impl ColumnDefinition {
+	/// Define a column of the given kind, stored at `offset` bytes into the
+	/// structured data of each row.
+	pub fn new(kind: ColumnKind, offset: u16) -> Self {
+		Self { kind, offset }
+	}
+}
+
 /// The kind of data structure stored in a column.
 #[allow(missing_docs)]
-#[binread]
+#[binrw]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IntoPrimitive)]
-#[br(big, repr = u16)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[brw(big, repr = u16)]
 #[repr(u16)]
 pub enum ColumnKind {
 	String = 0x0,
@@ -133,9 +196,9 @@ pub enum ColumnKind {
 }
 
 /// Metadata for a single sheet data page.
-#[binread]
+#[binrw]
 #[derive(Debug, Clone, Copy, CopyGetters)]
-#[br(big)]
+#[brw(big)]
 pub struct PageDefinition {
 	/// The first ID contained within the page.
 	#[get_copy = "pub"]
@@ -146,19 +209,68 @@ pub struct PageDefinition {
 	row_count: u32,
 }
 
-#[binread]
+impl PageDefinition {
+	/// Define a page containing the `row_count` row IDs following `start_id`.
+	pub fn new(start_id: u32, row_count: u32) -> Self {
+		Self {
+			start_id,
+			row_count,
+		}
+	}
+}
+
+#[binrw]
 #[derive(Debug)]
-#[br(big)]
+#[brw(big)]
 struct LanguageDefinition {
-	#[br(pad_after = 1)]
 	language: u8,
-	// unknown1: u8, //probably padding
+	unknown1: u8, // probably padding
 }
 
 impl LanguageDefinition {
 	// TODO: Consider utilising some other data structure - realistically a bitfield
 	// would be significantly smaller and more performant than a hash for this.
-	fn to_set(languages: Vec<Self>) -> HashSet<u8> {
+	fn to_set(languages: &[Self]) -> HashSet<u8> {
 		languages.iter().map(|language| language.language).collect()
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use crate::file::{File, WriteFile};
+
+	use super::{ColumnDefinition, ColumnKind, ExcelHeader, PageDefinition, SheetKind};
+
+	#[test]
+	fn round_trip() {
+		let header = ExcelHeader::new(
+			SheetKind::Default,
+			8,
+			vec![
+				ColumnDefinition::new(ColumnKind::String, 0),
+				ColumnDefinition::new(ColumnKind::Int32, 4),
+			],
+			vec![PageDefinition::new(0, 100), PageDefinition::new(100, 50)],
+			120,
+			vec![2, 1],
+		);
+
+		let mut bytes = Cursor::new(Vec::new());
+		header.write(&mut bytes).unwrap();
+		let bytes = bytes.into_inner();
+
+		let read = ExcelHeader::read(Cursor::new(bytes.clone())).unwrap();
+		assert_eq!(read.row_size(), 8);
+		assert_eq!(read.kind(), SheetKind::Default);
+		assert_eq!(read.columns()[1].kind(), ColumnKind::Int32);
+		assert_eq!(read.pages()[1].start_id(), 100);
+		assert_eq!(read.row_count(), 120);
+		assert!(read.languages().contains(&1));
+
+		let mut rewritten = Cursor::new(Vec::new());
+		read.write(&mut rewritten).unwrap();
+		assert_eq!(rewritten.into_inner(), bytes);
+	}
+}
@@ -1,22 +1,57 @@
 //! Structs and utilities for parsing .exl files.
 
-use std::{borrow::Cow, collections::HashSet};
+use std::{
+	borrow::Cow,
+	collections::HashSet,
+	io::{Seek, Write},
+};
 
 use crate::{
 	error::{Error, Result},
 	FileStream,
 };
 
-use super::File;
+use super::{File, WriteFile};
+
+const DEFAULT_MAGIC: &str = "EXLT,2";
+const UNKNOWN_ID: &str = "-1";
 
 /// List of known Excel sheets.
 #[derive(Debug)]
 pub struct ExcelList {
 	sheets: HashSet<String>,
+
+	// Original file layout, retained for writing.
+	magic: String,
+	entries: Vec<(String, String)>,
 }
 
 // TODO: should there be an impl intoiter for this?
 impl ExcelList {
+	/// Build a new, empty sheet list.
+	pub fn new() -> Self {
+		Self {
+			sheets: HashSet::new(),
+			magic: DEFAULT_MAGIC.into(),
+			entries: Vec::new(),
+		}
+	}
+
+	/// Add a sheet to the list. Sheets with an ID of `None` are not directly
+	/// referenced by ID within the game, and are recorded with an ID of `-1`.
+	pub fn insert(&mut self, sheet: impl Into<String>, id: Option<u32>) {
+		let sheet = sheet.into();
+		let id = id.map_or_else(|| UNKNOWN_ID.to_string(), |id| id.to_string());
+
+		match self.entries.iter_mut().find(|(name, _)| *name == sheet) {
+			Some(entry) => entry.1 = id,
+			None => {
+				self.sheets.insert(sheet.clone());
+				self.entries.push((sheet, id));
+			}
+		}
+	}
+
 	/// Iterate over known sheets in arbitrary order.
 	pub fn iter(&self) -> impl Iterator<Item = Cow<str>> {
 		self.sheets.iter().map(|name| name.into())
@@ -39,7 +74,8 @@ impl File for ExcelList {
 		let mut lines = list.split("\r\n");
 
 		// Ensure the first line contains the expected magic
-		let magic = lines.next().and_then(|line| line.get(0..4));
+		let magic_line = lines.next().unwrap_or_default();
+		let magic = magic_line.get(0..4);
 		if !matches!(magic, Some("EXLT")) {
 			return Err(Error::Resource(
 				format!("Incorrect magic in excel list file: expected \"EXLT\", got {magic:?}")
@@ -47,13 +83,41 @@ impl File for ExcelList {
 			));
 		}
 
-		// Build the set of sheets. We're ignoring the sheet ID (second field), as
-		// it's irrelevant for our usage.
-		let sheets = lines
-			.filter_map(|line| line.split_once(',').map(|split| split.0.to_string()))
+		// Sheet IDs are irrelevant for our usage, but are retained as-is so the
+		// list can be written back out unchanged.
+		let entries = lines
+			.filter_map(|line| {
+				line.split_once(',')
+					.map(|(name, id)| (name.to_string(), id.to_string()))
+			})
+			.collect::<Vec<_>>();
+
+		let sheets = entries
+			.iter()
+			.map(|(name, _)| name.clone())
 			.collect::<HashSet<_>>();
 
-		Ok(Self { sheets })
+		Ok(Self {
+			sheets,
+			magic: magic_line.to_string(),
+			entries,
+		})
+	}
+}
+
+impl WriteFile for ExcelList {
+	fn write(&self, mut stream: impl Write + Seek) -> Result<()> {
+		write!(stream, "{}\r\n", self.magic)?;
+		for (name, id) in &self.entries {
+			write!(stream, "{name},{id}\r\n")?;
+		}
+		Ok(())
+	}
+}
+
+impl Default for ExcelList {
+	fn default() -> Self {
+		Self::new()
 	}
 }
 
@@ -61,11 +125,15 @@ impl File for ExcelList {
 mod test {
 	use std::io::{self, Cursor};
 
-	use crate::{error::Error, file::File};
+	use crate::{
+		error::Error,
+		file::{File, WriteFile},
+	};
 
 	use super::ExcelList;
 
 	const TEST_LIST: &[u8] = b"EXLT\r\nsheet1,0\r\nsheet2,0\r\nsheet3,0\r\n";
+	const ROUND_TRIP_LIST: &[u8] = b"EXLT,2\r\nsheet1,0\r\nsheet2,-1\r\nsheet3,12\r\n";
 
 	#[test]
 	fn empty() {
@@ -90,4 +158,24 @@ mod test {
 		let list = ExcelList::read(Cursor::new(TEST_LIST)).unwrap();
 		assert!(!list.has("sheet4"));
 	}
+
+	#[test]
+	fn round_trip() {
+		let list = ExcelList::read(Cursor::new(ROUND_TRIP_LIST)).unwrap();
+		let mut bytes = Cursor::new(Vec::new());
+		list.write(&mut bytes).unwrap();
+		assert_eq!(bytes.into_inner(), ROUND_TRIP_LIST);
+	}
+
+	#[test]
+	fn insert_sheet() {
+		let mut list = ExcelList::new();
+		list.insert("sheet1", Some(0));
+		list.insert("sheet2", None);
+		assert!(list.has("sheet2"));
+
+		let mut bytes = Cursor::new(Vec::new());
+		list.write(&mut bytes).unwrap();
+		assert_eq!(bytes.into_inner(), b"EXLT,2\r\nsheet1,0\r\nsheet2,-1\r\n");
+	}
 }
@@ -1,3 +1,5 @@
+use std::io::{Seek, Write};
+
 use crate::{error::Result, FileStream};
 
 /// A file that can be read from ironworks.
@@ -6,6 +8,12 @@ pub trait File: Sized {
 	fn read(stream: impl FileStream) -> Result<Self>;
 }
 
+/// A file that can be written back out to its raw byte representation.
+pub trait WriteFile {
+	/// Write the raw byte representation of this file to the provided stream.
+	fn write(&self, stream: impl Write + Seek) -> Result<()>;
+}
+
 impl File for Vec<u8> {
 	fn read(mut stream: impl FileStream) -> Result<Self> {
 		let mut buffer = Vec::new();
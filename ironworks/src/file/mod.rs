@@ -25,4 +25,4 @@ pub mod sklb;
 #[cfg(feature = "tex")]
 pub mod tex;
 
-pub use file::File;
+pub use file::{File, WriteFile};
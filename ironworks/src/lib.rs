@@ -10,6 +10,8 @@ mod error;
 mod ironworks;
 mod utility;
 
+#[cfg(feature = "archive")]
+pub mod archive;
 #[cfg(feature = "excel")]
 pub mod excel;
 pub mod file;
@@ -11,10 +11,15 @@ use super::{
 	SeString,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expression {
 	// Inline values
 	U32(u32),
+	#[cfg_attr(
+		feature = "serde",
+		serde(serialize_with = "SeString::serialize_structured")
+	)]
 	String(SeString),
 
 	// PLaceholders
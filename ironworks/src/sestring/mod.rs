@@ -6,4 +6,8 @@ mod payload;
 mod sestring;
 mod value;
 
+#[cfg(feature = "excel")]
+pub(crate) use context::Context;
 pub use sestring::SeString;
+#[cfg(feature = "serde")]
+pub use sestring::Structured;
@@ -13,7 +13,8 @@ use super::{
 #[rustfmt::skip]
 #[non_exhaustive]
 #[binread]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Kind {
 	#[br(magic = 0x06_u8)] SetResetTime,
 	#[br(magic = 0x07_u8)] SetTime,
@@ -1,13 +1,14 @@
 use std::{
-	fmt,
-	io::{self, Read, Seek},
+	fmt::{self, Write},
+	io::{self, Cursor, Read, Seek, SeekFrom},
 	mem,
+	sync::OnceLock,
 };
 
-use binrw::{until_eof, BinRead, BinResult, ReadOptions};
+use binrw::{until_eof, BinRead, BinResult, Endian, ReadOptions};
 
 use crate::{
-	error::{Error, Result},
+	error::{Error, ErrorValue, Result},
 	utility::TakeSeekableExt,
 };
 
@@ -21,16 +22,35 @@ const PAYLOAD_END: u8 = 0x03;
 /// SeString data consists of standard UTF8 text interspersed with "payloads",
 /// which perform further operations ranging from text colour and style, to
 /// control flow and data lookups.
-#[derive(Debug)]
-pub struct SeString(Vec<Segment>);
+///
+/// Reading a SeString only locates the end of its data. Text and payloads are
+/// parsed on first use, such that strings that are never read are not parsed.
+#[derive(Debug, Clone, Default)]
+pub struct SeString {
+	raw: Vec<u8>,
+	// Parsed segments, or the message of the error encountered while parsing.
+	segments: OnceLock<Result<Vec<Segment>, String>>,
+}
 
 impl SeString {
+	/// Raw byte representation of this string, excluding the null terminator.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.raw
+	}
+
+	fn segments(&self) -> Result<&[Segment]> {
+		self.segments
+			.get_or_init(|| parse_segments(&self.raw).map_err(|error| error.to_string()))
+			.as_deref()
+			.map_err(|message| Error::Invalid(ErrorValue::SeString, message.clone()))
+	}
+
 	// TODO: Make this publicly accessible once context is a bit more fleshed out and usable.
 	pub(crate) fn resolve(&self, context: &mut Context) -> Result<String> {
-		let Self(segments) = self;
+		let segments = self.segments()?;
 
 		// Happy path - single segment can be treated as a pass-through.
-		if let [first] = &segments[..] {
+		if let [first] = segments {
 			return first.resolve(context);
 		}
 
@@ -42,6 +62,93 @@ impl SeString {
 
 		Ok(string)
 	}
+
+	// Plain text content of the string, with all payloads omitted.
+	pub(crate) fn text(&self) -> Result<String> {
+		let text = self
+			.segments()?
+			.iter()
+			.filter_map(|segment| match segment {
+				Segment::Text(string) => Some(string.as_str()),
+				Segment::Payload { .. } => None,
+			})
+			.collect();
+		Ok(text)
+	}
+
+	// Lossless textual form of the string, with payloads written as tags holding
+	// their raw bytes, i.e. `<hex:02100103>`.
+	pub(crate) fn tagged(&self) -> Result<String> {
+		let options = ReadOptions::new(Endian::Big);
+		let mut output = String::new();
+
+		let mut position = 0;
+		let mut text_start = 0;
+		while let Some(&byte) = self.raw.get(position) {
+			if byte != PAYLOAD_START {
+				position += 1;
+				continue;
+			}
+
+			output.push_str(&String::from_utf8_lossy(&self.raw[text_start..position]));
+
+			// Payloads are laid out as start marker, kind, length, arguments, end marker.
+			let mut cursor = Cursor::new(self.raw.get(position + 2..).unwrap_or(&[]));
+			let length = Expression::read_u32(&mut cursor, &options)?;
+			let length_size = usize::try_from(cursor.position()).unwrap();
+			let end = (position + 2 + length_size + usize::try_from(length).unwrap() + 1)
+				.min(self.raw.len());
+
+			output.push_str("<hex:");
+			for byte in &self.raw[position..end] {
+				write!(output, "{byte:02X}").unwrap();
+			}
+			output.push('>');
+
+			position = end;
+			text_start = end;
+		}
+
+		output.push_str(&String::from_utf8_lossy(&self.raw[text_start..]));
+
+		Ok(output)
+	}
+}
+
+/// Build a SeString consisting solely of plain text. Text containing null or
+/// payload start bytes is rejected, as it cannot be represented as plain text.
+impl TryFrom<&str> for SeString {
+	type Error = Error;
+
+	fn try_from(text: &str) -> Result<Self> {
+		Self::try_from(text.to_string())
+	}
+}
+
+impl TryFrom<String> for SeString {
+	type Error = Error;
+
+	fn try_from(text: String) -> Result<Self> {
+		if let Some(byte) = text
+			.bytes()
+			.find(|&byte| byte == 0 || byte == PAYLOAD_START)
+		{
+			return Err(Error::Invalid(
+				ErrorValue::SeString,
+				format!("plain text cannot contain byte {byte:#04x}"),
+			));
+		}
+
+		let raw = text.as_bytes().to_vec();
+		let segments = match text.is_empty() {
+			true => vec![],
+			false => vec![Segment::Text(text)],
+		};
+		Ok(Self {
+			raw,
+			segments: OnceLock::from(Ok(segments)),
+		})
+	}
 }
 
 /// Simple display implementation for SeString. Functions as a `.resolve` call
@@ -55,7 +162,49 @@ impl fmt::Display for SeString {
 	}
 }
 
+/// Serializes as resolved text, equivalent to the `Display` implementation. See
+/// [`SeString::structured`] for a lossless alternative.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SeString {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let text = self
+			.resolve(&mut Context::default())
+			.map_err(serde::ser::Error::custom)?;
+		serializer.serialize_str(&text)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl SeString {
+	/// Serializable view of this string as a tree of its text and payload
+	/// segments, with payload arguments retained as unresolved expressions.
+	pub fn structured(&self) -> Structured<'_> {
+		Structured(self)
+	}
+
+	pub(super) fn serialize_structured<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		let segments = self.segments().map_err(serde::ser::Error::custom)?;
+		serde::Serialize::serialize(segments, serializer)
+	}
+}
+
+/// Structured serialization of a SeString. See [`SeString::structured`].
+#[cfg(feature = "serde")]
 #[derive(Debug)]
+pub struct Structured<'a>(&'a SeString);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Structured<'_> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.0.serialize_structured(serializer)
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 enum Segment {
 	Text(String),
 	// TODO: consider if this should have a payload container struct rather than struct variant
@@ -96,7 +245,7 @@ impl BinRead for SeString {
 		options: &ReadOptions,
 		_args: Self::Args,
 	) -> BinResult<Self> {
-		let mut state = ReadState::default();
+		let mut raw = Vec::new();
 
 		loop {
 			match u8::read_options(reader, options, ()) {
@@ -104,17 +253,21 @@ impl BinRead for SeString {
 				Err(error) if error.is_eof() => break,
 				Ok(0) => break,
 
-				// PAYLOAD_START signifies the start of non-text payload.
+				// PAYLOAD_START signifies the start of non-text payload. Payloads may
+				// contain NULL bytes, so their length is read to skip over them.
 				Ok(PAYLOAD_START) => {
-					// Push the current text buffer as a segment.
-					state.push_buffer()?;
+					let start = reader.stream_position()? - 1;
+					let _kind = u8::read_options(reader, options, ())?;
+					let length = Expression::read_u32(reader, options)?;
+					let end = reader.stream_position()? + u64::from(length) + 1;
 
-					// Read and store the payload segment.
-					state.segments.push(read_payload_segment(reader, options)?);
+					reader.seek(SeekFrom::Start(start))?;
+					let length = reader.take(end - start).read_to_end(&mut raw)?;
 
 					// Ensure that we've reached a payload end marker.
-					let marker = u8::read_options(reader, options, ())?;
-					if marker != PAYLOAD_END {
+					if u64::try_from(length).unwrap() != end - start
+						|| raw.last() != Some(&PAYLOAD_END)
+					{
 						return Err(binrw::Error::AssertFail {
 							pos: reader.stream_position()?,
 							message: "payload missing end marker".into(),
@@ -122,14 +275,46 @@ impl BinRead for SeString {
 					}
 				}
 
-				maybe_byte => state.buffer.push(maybe_byte?),
+				maybe_byte => raw.push(maybe_byte?),
 			}
 		}
 
-		state.push_buffer()?;
+		Ok(Self {
+			raw,
+			segments: OnceLock::new(),
+		})
+	}
+}
+
+// Parse the text and payload segments of the raw bytes of a string.
+fn parse_segments(raw: &[u8]) -> BinResult<Vec<Segment>> {
+	let options = ReadOptions::new(Endian::Big);
+	let mut reader = Cursor::new(raw);
+	let mut state = ReadState::default();
+
+	loop {
+		match u8::read_options(&mut reader, &options, ()) {
+			Err(error) if error.is_eof() => break,
 
-		Ok(Self(state.segments))
+			Ok(PAYLOAD_START) => {
+				// Push the current text buffer as a segment.
+				state.push_buffer()?;
+
+				// Read and store the payload segment. The end marker has already been
+				// checked while reading the string.
+				state
+					.segments
+					.push(read_payload_segment(&mut reader, &options)?);
+				u8::read_options(&mut reader, &options, ())?;
+			}
+
+			maybe_byte => state.buffer.push(maybe_byte?),
+		}
 	}
+
+	state.push_buffer()?;
+
+	Ok(state.segments)
 }
 
 fn read_payload_segment<R: Read + Seek>(
@@ -158,11 +343,58 @@ impl ReadState {
 		}
 
 		let bytes = mem::take(&mut self.buffer);
-		let string = String::from_utf8(bytes)
-			.map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+		let string = String::from_utf8(bytes).map_err(io::Error::other)?;
 
 		self.segments.push(Segment::Text(string));
 
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use binrw::BinReaderExt;
+
+	use super::SeString;
+
+	fn read(bytes: &[u8]) -> SeString {
+		Cursor::new(bytes).read_be::<SeString>().unwrap()
+	}
+
+	#[test]
+	fn plain_text() {
+		let string = SeString::try_from("hello").unwrap();
+		assert_eq!(string.as_bytes(), b"hello");
+		assert_eq!(string.text().unwrap(), "hello");
+	}
+
+	#[test]
+	fn plain_text_rejects_control_bytes() {
+		SeString::try_from("null\0byte").unwrap_err();
+		SeString::try_from("payload\x02byte").unwrap_err();
+	}
+
+	#[test]
+	fn payload_null_byte() {
+		// Payload data may contain NULL bytes, which do not end the string.
+		let string = read(b"a\x02\x16\x02\x00\x03b\0c");
+		assert_eq!(string.as_bytes(), b"a\x02\x16\x02\x00\x03b");
+	}
+
+	#[test]
+	fn lazy_parse() {
+		// Invalid text is only reported once the string is parsed.
+		let string = read(b"\xff\x02\x16\x01\x03");
+		assert_eq!(string.as_bytes(), b"\xff\x02\x16\x01\x03");
+		string.text().unwrap_err();
+	}
+
+	#[test]
+	fn missing_end_marker() {
+		Cursor::new(b"a\x02\x16\x01b")
+			.read_be::<SeString>()
+			.unwrap_err();
+	}
+}
@@ -1,8 +1,11 @@
 use std::io::{Cursor, Empty, Read, Seek, SeekFrom};
 
-use binrw::BinRead;
+use binrw::BinReaderExt;
 
-use crate::{error::Result, sqpack::block::BlockStream};
+use crate::{
+	error::Result,
+	sqpack::{block::BlockStream, Platform},
+};
 
 use super::{
 	empty, model,
@@ -19,16 +22,26 @@ pub struct File<R> {
 
 impl<R: Read + Seek> File<R> {
 	/// Create a new File which which will translate SqPack stored data in the given stream.
-	pub fn new(mut reader: R) -> Result<Self> {
+	pub fn new(reader: R) -> Result<Self> {
+		Self::with_platform(reader, Platform::Win32)
+	}
+
+	/// Create a new File which which will translate SqPack stored data in the
+	/// given stream, using the data layout of the specified platform.
+	pub fn with_platform(mut reader: R, platform: Platform) -> Result<Self> {
+		let endian = platform.endian();
+
 		// Read in the header.
-		let header = Header::read(&mut reader)?;
+		let header = reader.read_type::<Header>(endian)?;
 
 		use FileStreamKind as FSK;
 		let file_stream = match &header.kind {
 			FileKind::Empty => FSK::Empty(empty::read(reader, header)?),
-			FileKind::Standard => FSK::Standard(standard::read(reader, header.size, header)?),
-			FileKind::Model => FSK::Model(model::read(reader, header.size, header)?),
-			FileKind::Texture => FSK::Texture(texture::read(reader, header.size, header)?),
+			FileKind::Standard => {
+				FSK::Standard(standard::read(reader, header.size, header, endian)?)
+			}
+			FileKind::Model => FSK::Model(model::read(reader, header.size, header, endian)?),
+			FileKind::Texture => FSK::Texture(texture::read(reader, header.size, header, endian)?),
 		};
 
 		Ok(File { inner: file_stream })
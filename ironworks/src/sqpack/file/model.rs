@@ -1,6 +1,6 @@
 use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
-use binrw::{binread, BinRead, BinWriterExt, VecArgs};
+use binrw::{binread, BinRead, BinReaderExt, BinWriterExt, Endian, VecArgs};
 
 use crate::{error::Result, sqpack::block::read_block};
 
@@ -10,7 +10,6 @@ const MAX_LODS: usize = 3;
 
 #[binread]
 #[derive(Debug)]
-#[br(little)]
 struct ModelHeader {
 	_size: SectionInfo<u32>,
 	_compressed_size: SectionInfo<u32>,
@@ -27,7 +26,6 @@ struct ModelHeader {
 
 #[binread]
 #[derive(Debug)]
-#[br(little)]
 struct SectionInfo<T: BinRead<Args = ()> + 'static> {
 	stack: T,
 	runtime: T,
@@ -36,8 +34,13 @@ struct SectionInfo<T: BinRead<Args = ()> + 'static> {
 	index_buffer: [T; MAX_LODS],
 }
 
-pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
-	let model_header = ModelHeader::read(&mut reader)?;
+pub fn read(
+	mut reader: impl Read + Seek,
+	offset: u32,
+	header: Header,
+	endian: Endian,
+) -> Result<Cursor<Vec<u8>>> {
+	let model_header = reader.read_type::<ModelHeader>(endian)?;
 
 	// Model header is followed by an array of block sizes.
 	let block_counts = &model_header.block_count;
@@ -48,8 +51,8 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 		+ block_counts.index_buffer.iter().sum::<u16>();
 
 	// TODO: i should probably make an impl for this it's pretty repetetive
-	let block_sizes = <Vec<u16>>::read_le_args(
-		&mut reader,
+	let block_sizes = reader.read_type_args::<Vec<u16>>(
+		endian,
 		VecArgs {
 			count: total_blocks.try_into().unwrap(),
 			inner: (),
@@ -72,6 +75,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 		&block_sizes,
 		&mut reader,
 		&mut writer,
+		endian,
 	)?;
 
 	// Runtime
@@ -82,6 +86,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 		&block_sizes,
 		&mut reader,
 		&mut writer,
+		endian,
 	)?;
 
 	// LOD level data
@@ -106,6 +111,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 				&block_sizes,
 				&mut reader,
 				&mut writer,
+				endian,
 			)?;
 		}
 
@@ -119,6 +125,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 				&block_sizes,
 				&mut reader,
 				&mut writer,
+				endian,
 			)?;
 		}
 
@@ -136,6 +143,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 				&block_sizes,
 				&mut reader,
 				&mut writer,
+				endian,
 			)?;
 		}
 	}
@@ -143,19 +151,19 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 	// Write out the header now we've collected the info for it.
 	// TODO: While these values do work, it's technically not a match with the game's own format - the `_size` property in the header has the correct final values, but they're 0-padded, leading to larger sizes than we get with this method. Look into fixing this up to get as close to 1:1 as possible.
 	writer.rewind()?;
-	writer.write_le(&header.block_count)?; // version
-	writer.write_le(&stack_size)?;
-	writer.write_le(&runtime_size)?;
-	writer.write_le(&model_header.vertex_declaration_count)?;
-	writer.write_le(&model_header.material_count)?;
-	writer.write_le(&vertex_data_offsets)?;
-	writer.write_le(&index_data_offsets)?;
-	writer.write_le(&vertex_buffer_sizes)?;
-	writer.write_le(&index_buffer_sizes)?;
-	writer.write_le(&model_header.lod_count)?;
-	writer.write_le(&model_header.index_buffer_streaming_enabled)?;
-	writer.write_le(&model_header.edge_geometry_enabled)?;
-	writer.write_le(&0u8)?;
+	writer.write_type(&header.block_count, endian)?; // version
+	writer.write_type(&stack_size, endian)?;
+	writer.write_type(&runtime_size, endian)?;
+	writer.write_type(&model_header.vertex_declaration_count, endian)?;
+	writer.write_type(&model_header.material_count, endian)?;
+	writer.write_type(&vertex_data_offsets, endian)?;
+	writer.write_type(&index_data_offsets, endian)?;
+	writer.write_type(&vertex_buffer_sizes, endian)?;
+	writer.write_type(&index_buffer_sizes, endian)?;
+	writer.write_type(&model_header.lod_count, endian)?;
+	writer.write_type(&model_header.index_buffer_streaming_enabled, endian)?;
+	writer.write_type(&model_header.edge_geometry_enabled, endian)?;
+	writer.write_type(&0u8, endian)?;
 
 	// TODO: Look into lazy reading this. Can probably read LODs lazily?
 	writer.rewind()?;
@@ -169,6 +177,7 @@ fn read_blocks(
 	block_sizes: &[u16],
 	reader: &mut (impl Read + Seek),
 	writer: &mut impl Write,
+	endian: Endian,
 ) -> Result<u32> {
 	let size = (0..block_count)
 		// Calculate the offsets for the blocks.
@@ -179,7 +188,7 @@ fn read_blocks(
 		})
 		// Read the blocks into the cursor, recording the read byte count.
 		.try_fold(0u32, |size, offset| -> Result<u32> {
-			let bytes_read = io::copy(&mut read_block(reader, offset)?, writer)?;
+			let bytes_read = io::copy(&mut read_block(reader, offset, endian)?, writer)?;
 			Ok(size + u32::try_from(bytes_read).unwrap())
 		})?;
 
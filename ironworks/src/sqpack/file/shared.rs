@@ -1,8 +1,10 @@
 use binrw::binread;
 
+// Endianness of file structures is dependant on the platform of the SqPack, and
+// must be provided by the caller.
+
 #[binread]
 #[derive(Debug)]
-#[br(little)]
 pub struct Header {
 	pub size: u32,
 	pub kind: FileKind,
@@ -15,7 +17,7 @@ pub struct Header {
 
 #[binread]
 #[derive(Debug)]
-#[br(little, repr = u32)]
+#[br(repr = u32)]
 pub enum FileKind {
 	Empty = 1,
 	Standard,
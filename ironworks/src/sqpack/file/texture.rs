@@ -1,13 +1,12 @@
 use std::io::{self, Cursor, Read, Seek, SeekFrom};
 
-use binrw::{binread, BinRead, VecArgs};
+use binrw::{binread, BinReaderExt, Endian, VecArgs};
 
 use crate::{error::Result, sqpack::block::read_block};
 
 use super::shared::Header;
 
 #[binread]
-#[br(little)]
 #[derive(Debug)]
 struct SurfaceBlockInfo {
 	compressed_offset: u32,
@@ -18,7 +17,6 @@ struct SurfaceBlockInfo {
 }
 
 #[binread]
-#[br(little)]
 #[derive(Debug)]
 struct TexHeader {
 	attribute: u32,
@@ -34,10 +32,15 @@ struct TexHeader {
 	surface_offsets: [u32; 13],
 }
 
-pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
+pub fn read(
+	mut reader: impl Read + Seek,
+	offset: u32,
+	header: Header,
+	endian: Endian,
+) -> Result<Cursor<Vec<u8>>> {
 	// Eagerly read the block info.
-	let blocks = <Vec<SurfaceBlockInfo>>::read_args(
-		&mut reader,
+	let blocks = reader.read_type_args::<Vec<SurfaceBlockInfo>>(
+		endian,
 		VecArgs {
 			count: header.block_count.try_into().unwrap(),
 			inner: (),
@@ -49,8 +52,8 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 		.iter()
 		.fold(0, |total, block| total + block.block_count);
 
-	let sub_block_offsets = <Vec<u16>>::read_le_args(
-		&mut reader,
+	let sub_block_offsets = reader.read_type_args::<Vec<u16>>(
+		endian,
 		VecArgs {
 			count: sub_block_count.try_into().unwrap(),
 			inner: (),
@@ -69,7 +72,7 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 	let raw_header_size = blocks[0].compressed_offset;
 	if raw_header_size > 0 {
 		reader.seek(SeekFrom::Start(offset.into()))?;
-		texture_header = Some(TexHeader::read(&mut reader)?);
+		texture_header = Some(reader.read_type::<TexHeader>(endian)?);
 
 		reader.seek(SeekFrom::Start(offset.into()))?;
 		io::copy(
@@ -113,7 +116,10 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 			.skip(usize::try_from(block.block_offset).unwrap())
 			.take(usize::try_from(block.block_count).unwrap())
 		{
-			io::copy(&mut read_block(&mut reader, data_offset)?, &mut writer)?;
+			io::copy(
+				&mut read_block(&mut reader, data_offset, endian)?,
+				&mut writer,
+			)?;
 			data_offset += u32::from(*sub_block_offset);
 		}
 	}
@@ -0,0 +1,49 @@
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Hasher that passes `u64` values through as-is. Index hashes are already
+/// well-distributed CRC values, so re-hashing them is wasted work.
+#[derive(Debug, Default)]
+pub struct PassThroughHasher(u64);
+
+impl Hasher for PassThroughHasher {
+	fn finish(&self) -> u64 {
+		self.0
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		// Only u64 keys are expected, but fold anything else into the state so
+		// other key types still hash consistently.
+		for &byte in bytes {
+			self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+		}
+	}
+
+	fn write_u64(&mut self, value: u64) {
+		self.0 = value;
+	}
+}
+
+pub type BuildPassThroughHasher = BuildHasherDefault<PassThroughHasher>;
+
+#[cfg(test)]
+mod test {
+	use std::hash::Hasher;
+
+	use super::PassThroughHasher;
+
+	#[test]
+	fn pass_through() {
+		let mut hasher = PassThroughHasher::default();
+		hasher.write_u64(0x1234);
+		assert_eq!(hasher.finish(), 0x1234);
+	}
+
+	#[test]
+	fn bytes() {
+		let mut a = PassThroughHasher::default();
+		a.write(b"exd/root.exl");
+		let mut b = PassThroughHasher::default();
+		b.write(b"exd/root.exh");
+		assert_ne!(a.finish(), b.finish());
+	}
+}
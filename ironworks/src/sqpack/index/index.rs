@@ -1,17 +1,21 @@
 use std::sync::{Arc, Mutex};
 
 use binrw::BinRead;
-use getset::CopyGetters;
+use getset::{CopyGetters, Getters};
 
 use crate::{
 	error::{Error, ErrorValue, Result},
-	sqpack::Resource,
+	sqpack::{Platform, Resource},
 };
 
-use super::{index1::Index1, index2::Index2, shared::FileMetadata};
+use super::{
+	index1::{hash, Index1},
+	index2::Index2,
+	shared::{ChunkEntry, FileMetadata},
+};
 
 /// Specifier of a file location within a SqPack category.
-#[derive(Debug, CopyGetters)]
+#[derive(Debug, Clone, CopyGetters)]
 #[get_copy = "pub"]
 pub struct Location {
 	/// SqPack chunk the file is in, i.e. `0000XX.win32.dat1`.
@@ -23,6 +27,37 @@ pub struct Location {
 	/// Estimated size of the target file, if known. This will typically err on
 	/// the larger side, as files commonly have some amount of padding at the end.
 	size: Option<u32>,
+	/// Platform the containing SqPack was built for.
+	platform: Platform,
+}
+
+/// A file listed in a SqPack index.
+#[derive(Debug, Getters, CopyGetters)]
+pub struct IndexEntry {
+	/// Repository the file is in.
+	#[get_copy = "pub"]
+	repository: u8,
+	/// Category the file is in.
+	#[get_copy = "pub"]
+	category: u8,
+	/// Hash of the file's path, as used for lookups within the index.
+	#[get_copy = "pub"]
+	hash: u64,
+	/// Full path of the file, if known. Indexes only record the paths of files
+	/// whose hash collides with that of another file.
+	#[get = "pub"]
+	path: Option<String>,
+	/// Location of the file's data.
+	#[get = "pub"]
+	location: Location,
+}
+
+impl IndexEntry {
+	/// Check if this entry's hash collides with one or more other entries in the
+	/// same index.
+	pub fn is_collision(&self) -> bool {
+		self.path.is_some()
+	}
 }
 
 #[derive(Debug)]
@@ -46,8 +81,10 @@ impl<R: Resource> Index<R> {
 		})
 	}
 
-	pub fn find(&self, path: &str) -> Result<Location> {
-		let location = self.chunks().find_map(|chunk| {
+	pub fn entry(&self, path: &str) -> Result<IndexEntry> {
+		let hash = hash(path)?;
+
+		let entry = self.chunks().find_map(|chunk| {
 			let (index, chunk) = match chunk {
 				Ok(value) => value,
 				Err(error) => return Some(Err(error)),
@@ -56,21 +93,53 @@ impl<R: Resource> Index<R> {
 			match chunk.find(path) {
 				Err(Error::NotFound(_)) => None,
 				Err(error) => Some(Err(error)),
-				Ok((meta, size)) => Some(Ok(Location {
-					chunk: index,
-					data_file: meta.data_file_id,
-					offset: meta.offset,
-					size,
+				Ok((meta, size)) => Some(Ok(IndexEntry {
+					repository: self.repository,
+					category: self.category,
+					hash,
+					path: meta.is_synonym.then(|| path.into()),
+					location: Location {
+						chunk: index,
+						data_file: meta.data_file_id,
+						offset: meta.offset,
+						size,
+						platform: chunk.platform(),
+					},
 				})),
 			}
 		});
 
-		match location {
+		match entry {
 			None => Err(Error::NotFound(ErrorValue::Path(path.into()))),
 			Some(result) => result,
 		}
 	}
 
+	pub fn entries(&self) -> Result<Vec<IndexEntry>> {
+		let mut entries = Vec::new();
+
+		for chunk in self.chunks() {
+			let (index, chunk) = chunk?;
+			let platform = chunk.platform();
+
+			entries.extend(chunk.entries()?.map(|entry| IndexEntry {
+				repository: self.repository,
+				category: self.category,
+				hash: entry.hash,
+				path: entry.path,
+				location: Location {
+					chunk: index,
+					data_file: entry.metadata.data_file_id,
+					offset: entry.metadata.offset,
+					size: entry.size,
+					platform,
+				},
+			}));
+		}
+
+		Ok(entries)
+	}
+
 	fn chunks(&self) -> impl Iterator<Item = Result<(u8, Arc<IndexChunk>)>> + '_ {
 		// Get the max known chunk ID. If we don't know it, we want to loop the full potential ID space (u8).
 		let guard = self.max_chunk.lock().unwrap();
@@ -141,10 +210,92 @@ impl IndexChunk {
 			})
 	}
 
+	fn platform(&self) -> Platform {
+		match self {
+			Self::Index1(index) => index.platform(),
+			Self::Index2(index) => index.platform(),
+		}
+	}
+
 	fn find(&self, path: &str) -> Result<(FileMetadata, Option<u32>)> {
 		match self {
 			Self::Index1(index) => index.find(path),
-			Self::Index2(_index) => todo!("index2"),
+			Self::Index2(_index) => Err(index2_unsupported()),
 		}
 	}
+
+	fn entries(&self) -> Result<impl Iterator<Item = ChunkEntry> + '_> {
+		match self {
+			Self::Index1(index) => Ok(index.entries()),
+			Self::Index2(_index) => Err(index2_unsupported()),
+		}
+	}
+}
+
+// TODO: remove once index2 lookups are implemented.
+fn index2_unsupported() -> Error {
+	Error::Invalid(
+		ErrorValue::Other("index2 chunk".into()),
+		"reading index2 chunks is not supported".into(),
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use std::{io::Cursor, sync::Arc};
+
+	use crate::{
+		error::{Error, ErrorValue, Result},
+		sqpack::Resource,
+	};
+
+	use super::{Index, Location};
+
+	// Resource providing a single index2 chunk, with no index1 chunks.
+	struct Index2Resource;
+
+	impl Resource for Index2Resource {
+		fn version(&self, _repository: u8) -> Result<String> {
+			Ok("test".into())
+		}
+
+		type Index = Cursor<Vec<u8>>;
+		fn index(&self, _repository: u8, _category: u8, chunk: u8) -> Result<Self::Index> {
+			Err(Error::NotFound(ErrorValue::Other(format!("index {chunk}"))))
+		}
+
+		type Index2 = Cursor<Vec<u8>>;
+		fn index2(&self, _repository: u8, _category: u8, chunk: u8) -> Result<Self::Index2> {
+			if chunk != 0 {
+				return Err(Error::NotFound(ErrorValue::Other(format!(
+					"index2 {chunk}"
+				))));
+			}
+
+			let mut bytes = b"SqPack\0\0".to_vec();
+			bytes.extend([0u8; 4]);
+			bytes.extend(0x400u32.to_le_bytes());
+			bytes.resize(0x400, 0);
+			Ok(Cursor::new(bytes))
+		}
+
+		type File = Cursor<Vec<u8>>;
+		fn file(&self, _repository: u8, _category: u8, _location: Location) -> Result<Self::File> {
+			Err(Error::NotFound(ErrorValue::Other("file".into())))
+		}
+	}
+
+	#[test]
+	fn index2_entries() {
+		let index = Index::new(0, 0, Arc::new(Index2Resource)).unwrap();
+		let error = index.entries().unwrap_err();
+		assert!(matches!(error, Error::Invalid(_, _)), "{error}");
+	}
+
+	#[test]
+	fn index2_entry() {
+		let index = Index::new(0, 0, Arc::new(Index2Resource)).unwrap();
+		let error = index.entry("exd/root.exl").unwrap_err();
+		assert!(matches!(error, Error::Invalid(_, _)), "{error}");
+	}
 }
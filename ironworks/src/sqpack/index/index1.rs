@@ -1,17 +1,23 @@
-use std::{collections::BTreeSet, io::SeekFrom};
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::SeekFrom,
+};
 
 use binrw::binread;
 
-use crate::error::{Error, ErrorValue, Result};
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sqpack::Platform,
+};
 
 use super::{
 	crc::crc32,
-	shared::{FileMetadata, IndexHeader, SqPackHeader},
+	hasher::BuildPassThroughHasher,
+	shared::{ChunkEntry, FileMetadata, IndexHeader, SqPackHeader},
 };
 
 #[binread]
 #[derive(Debug)]
-#[br(little)]
 struct Entry {
 	hash: u64,
 	#[br(pad_after = 4)]
@@ -23,6 +29,30 @@ impl Entry {
 	const SIZE: u32 = 16;
 }
 
+#[binread]
+#[derive(Debug)]
+struct Synonym {
+	hash: u64,
+	#[br(pad_after = 4)]
+	file_metadata: FileMetadata,
+	// index: u32,
+	#[br(map = |bytes: [u8; Synonym::PATH_SIZE]| read_path(&bytes))]
+	path: String,
+}
+
+impl Synonym {
+	const PATH_SIZE: usize = 240;
+	const SIZE: u32 = 256;
+}
+
+fn read_path(bytes: &[u8]) -> String {
+	let length = bytes
+		.iter()
+		.position(|&byte| byte == 0)
+		.unwrap_or(bytes.len());
+	String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
+
 #[binread]
 #[derive(Debug)]
 #[br(little)]
@@ -30,64 +60,306 @@ pub struct Index1 {
 	#[br(temp)]
 	sqpack_header: SqPackHeader,
 
-	#[br(temp, seek_before = SeekFrom::Start(sqpack_header.size.into()))]
+	#[br(calc = sqpack_header.platform)]
+	platform: Platform,
+
+	#[br(
+		temp,
+		seek_before = SeekFrom::Start(sqpack_header.size.into()),
+		is_big = platform.is_big_endian(),
+	)]
 	index_header: IndexHeader,
 
 	#[br(
 		seek_before = SeekFrom::Start(index_header.index_data.offset.into()),
 		count = index_header.index_data.size / Entry::SIZE,
+		is_big = platform.is_big_endian(),
 	)]
 	indexes: Vec<Entry>,
 
-	#[br(calc = indexes.iter().map(|entry| (
-		entry.file_metadata.data_file_id,
-		entry.file_metadata.offset
-	)).collect())]
+	// Lookup table from hash to position in the index table. Where hashes are
+	// duplicated in the index table, the first entry is used.
+	#[br(calc = build_lookup(&indexes))]
+	lookup: HashMap<u64, usize, BuildPassThroughHasher>,
+
+	// The synonym table contains the full path of every file that shares its
+	// hash with another file, alongside the real metadata for that file.
+	#[br(
+		seek_before = SeekFrom::Start(index_header.synonym_data.offset.into()),
+		count = index_header.synonym_data.size / Synonym::SIZE,
+		is_big = platform.is_big_endian(),
+		map = |synonyms: Vec<Synonym>| synonyms
+			.into_iter()
+			.filter(|synonym| !synonym.path.is_empty())
+			.collect(),
+	)]
+	synonyms: Vec<Synonym>,
+
+	#[br(calc = indexes.iter()
+		.map(|entry| &entry.file_metadata)
+		.chain(synonyms.iter().map(|synonym| &synonym.file_metadata))
+		.filter(|metadata| !metadata.is_synonym)
+		.map(|metadata| (metadata.data_file_id, metadata.offset))
+		.collect()
+	)]
 	offsets: BTreeSet<(u8, u32)>,
 }
 
 impl Index1 {
+	pub fn platform(&self) -> Platform {
+		self.platform
+	}
+
 	pub fn find(&self, path: &str) -> Result<(FileMetadata, Option<u32>)> {
-		// Calculate the Index1 hash of the path
-		let hashed_segments = path
-			.rsplitn(2, '/')
-			.map(|segment| crc32(segment.as_bytes()))
-			.collect::<Vec<_>>();
-
-		let hash = match hashed_segments[..] {
-			[file, directory] => (directory as u64) << 32 | file as u64,
-			_ => {
-				return Err(Error::Invalid(
-					ErrorValue::Path(path.into()),
-					"Paths must contain at least two segments.".into(),
-				))
-			}
-		};
+		let hash = hash(path)?;
 
 		// Look for a matching entry in the index table
-		// TODO: hashmap this probably
-		// TODO: i saw a neat impl that was a pass-through hasher for a map to save time on hashing small values. maybe?
-		self.indexes
+		let entry = self
+			.lookup
+			.get(&hash)
+			.map(|&position| &self.indexes[position])
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+
+		// If the entry is marked as a synonym, the hash is shared by multiple files
+		// - disambiguate using the full path stored in the synonym table.
+		let metadata = match entry.file_metadata.is_synonym {
+			false => &entry.file_metadata,
+			true => self
+				.synonyms
+				.iter()
+				.find(|synonym| synonym.hash == hash && synonym.path.eq_ignore_ascii_case(path))
+				.map(|synonym| &synonym.file_metadata)
+				.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?,
+		};
+
+		// Retain the synonym flag of the main entry, such that collisions can be
+		// identified by callers.
+		let file_metadata = FileMetadata {
+			is_synonym: entry.file_metadata.is_synonym,
+			..metadata.clone()
+		};
+
+		Ok((file_metadata, self.size(metadata)))
+	}
+
+	pub fn entries(&self) -> impl Iterator<Item = ChunkEntry> + '_ {
+		// Synonym entries in the main table do not point to a real file, their
+		// actual metadata is listed, by path, in the synonym table.
+		let entries = self
+			.indexes
 			.iter()
-			.find(|entry| entry.hash == hash)
-			.map(|entry| {
-				let metadata = entry.file_metadata.clone();
-
-				// Look up the offset after this meta, if any exists. The result's data
-				// file ID is double checked to ensure we don't return cross-dat offsets
-				// - this could occur if the requested file is the last file in a dat,
-				// but further dats exist.
-				let size = self
-					.offsets
-					.range((metadata.data_file_id, metadata.offset + 1)..)
-					.next()
-					.and_then(|(dat_id, offset)| match *dat_id == metadata.data_file_id {
-						true => Some(offset - metadata.offset),
-						false => None,
-					});
-
-				(metadata, size)
+			.filter(|entry| !entry.file_metadata.is_synonym)
+			.map(|entry| (entry.hash, None, &entry.file_metadata));
+
+		let synonyms = self.synonyms.iter().map(|synonym| {
+			(
+				synonym.hash,
+				Some(synonym.path.clone()),
+				&synonym.file_metadata,
+			)
+		});
+
+		entries
+			.chain(synonyms)
+			.map(|(hash, path, metadata)| ChunkEntry {
+				hash,
+				path,
+				metadata: metadata.clone(),
+				size: self.size(metadata),
+			})
+	}
+
+	fn size(&self, metadata: &FileMetadata) -> Option<u32> {
+		// Look up the offset after this meta, if any exists. The result's data
+		// file ID is double checked to ensure we don't return cross-dat offsets
+		// - this could occur if the requested file is the last file in a dat,
+		// but further dats exist.
+		self.offsets
+			.range((metadata.data_file_id, metadata.offset + 1)..)
+			.next()
+			.and_then(|(dat_id, offset)| match *dat_id == metadata.data_file_id {
+				true => Some(offset - metadata.offset),
+				false => None,
 			})
-			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))
+	}
+}
+
+fn build_lookup(entries: &[Entry]) -> HashMap<u64, usize, BuildPassThroughHasher> {
+	let mut lookup =
+		HashMap::with_capacity_and_hasher(entries.len(), BuildPassThroughHasher::default());
+	for (position, entry) in entries.iter().enumerate() {
+		lookup.entry(entry.hash).or_insert(position);
+	}
+	lookup
+}
+
+/// Calculate the Index1 hash of a path.
+pub fn hash(path: &str) -> Result<u64> {
+	let hashed_segments = path
+		.rsplitn(2, '/')
+		.map(|segment| crc32(segment.as_bytes()))
+		.collect::<Vec<_>>();
+
+	match hashed_segments[..] {
+		[file, directory] => Ok((directory as u64) << 32 | file as u64),
+		_ => Err(Error::Invalid(
+			ErrorValue::Path(path.into()),
+			"Paths must contain at least two segments.".into(),
+		)),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use binrw::BinRead;
+
+	use crate::sqpack::Platform;
+
+	use super::{hash, Index1};
+
+	const ENTRIES_OFFSET: u32 = 0x800;
+
+	// Build the bytes of a minimal index file. Entries are specified as
+	// (hash, synonym, data file, offset), synonyms additionally specify a path.
+	fn build_index(entries: &[(u64, bool, u8, u32)], synonyms: &[(u64, u8, u32, &str)]) -> Vec<u8> {
+		build_platform_index(Platform::Win32, entries, synonyms)
+	}
+
+	fn build_platform_index(
+		platform: Platform,
+		entries: &[(u64, bool, u8, u32)],
+		synonyms: &[(u64, u8, u32, &str)],
+	) -> Vec<u8> {
+		let big = platform.is_big_endian();
+		let u32_bytes = |value: u32| match big {
+			true => value.to_be_bytes(),
+			false => value.to_le_bytes(),
+		};
+		let u64_bytes = |value: u64| match big {
+			true => value.to_be_bytes(),
+			false => value.to_le_bytes(),
+		};
+
+		let metadata = |synonym: bool, data_file: u8, offset: u32| {
+			u32_bytes((offset / 8) | (u32::from(data_file) << 1) | u32::from(synonym))
+		};
+
+		let section = |bytes: &mut Vec<u8>, offset: u32, size: u32| {
+			bytes.extend(u32_bytes(offset));
+			bytes.extend(u32_bytes(size));
+			bytes.extend([0u8; 64]);
+		};
+
+		let entries_size = u32::try_from(entries.len() * 16).unwrap();
+		let synonyms_size = u32::try_from(synonyms.len() * 256).unwrap();
+
+		let mut bytes = Vec::new();
+
+		// SqPack header.
+		bytes.extend(b"SqPack\0\0");
+		bytes.extend([platform as u8, 0, 0, 0]);
+		bytes.extend(u32_bytes(0x400));
+		bytes.resize(0x400, 0);
+
+		// Index header.
+		bytes.extend(u32_bytes(0x400));
+		bytes.extend(u32_bytes(1));
+		section(&mut bytes, ENTRIES_OFFSET, entries_size);
+		bytes.extend(u32_bytes(1));
+		section(&mut bytes, ENTRIES_OFFSET + entries_size, synonyms_size);
+		section(&mut bytes, 0, 0);
+		section(&mut bytes, 0, 0);
+		bytes.resize(usize::try_from(ENTRIES_OFFSET).unwrap(), 0);
+
+		for &(hash, synonym, data_file, offset) in entries {
+			bytes.extend(u64_bytes(hash));
+			bytes.extend(metadata(synonym, data_file, offset));
+			bytes.extend([0u8; 4]);
+		}
+
+		for &(hash, data_file, offset, path) in synonyms {
+			bytes.extend(u64_bytes(hash));
+			bytes.extend(metadata(false, data_file, offset));
+			bytes.extend([0u8; 4]);
+			let mut path_bytes = path.as_bytes().to_vec();
+			path_bytes.resize(240, 0);
+			bytes.extend(path_bytes);
+		}
+
+		bytes
+	}
+
+	#[test]
+	fn find() {
+		let path = "exd/root.exl";
+		let bytes = build_index(&[(hash(path).unwrap(), false, 0, 0x100)], &[]);
+		let index = Index1::read(&mut Cursor::new(bytes)).unwrap();
+
+		let (metadata, size) = index.find(path).unwrap();
+		assert_eq!(metadata.offset, 0x100);
+		assert_eq!(size, None);
+
+		index.find("exd/missing.exl").unwrap_err();
+	}
+
+	#[test]
+	fn find_synonym() {
+		let path = "exd/root.exl";
+		let path_hash = hash(path).unwrap();
+		let bytes = build_index(
+			&[(path_hash, true, 0, 0)],
+			&[
+				(path_hash, 0, 0x100, "exd/collision.exl"),
+				(path_hash, 0, 0x200, path),
+			],
+		);
+		let index = Index1::read(&mut Cursor::new(bytes)).unwrap();
+
+		let (metadata, size) = index.find(path).unwrap();
+		assert!(metadata.is_synonym);
+		assert_eq!(metadata.offset, 0x200);
+		assert_eq!(size, None);
+	}
+
+	#[test]
+	fn entries_report_collisions() {
+		let path_hash = hash("exd/root.exl").unwrap();
+		let bytes = build_index(
+			&[(1, false, 0, 0x80), (path_hash, true, 0, 0)],
+			&[
+				(path_hash, 0, 0x100, "exd/collision.exl"),
+				(path_hash, 0, 0x200, "exd/root.exl"),
+			],
+		);
+		let index = Index1::read(&mut Cursor::new(bytes)).unwrap();
+
+		let entries = index.entries().collect::<Vec<_>>();
+		assert_eq!(entries.len(), 3);
+		assert_eq!(entries[0].path, None);
+		assert_eq!(entries[0].size, Some(0x80));
+		assert_eq!(entries[1].path.as_deref(), Some("exd/collision.exl"));
+		assert_eq!(entries[2].path.as_deref(), Some("exd/root.exl"));
+	}
+
+	#[test]
+	fn find_big_endian() {
+		let path = "exd/root.exl";
+		let bytes = build_platform_index(
+			Platform::PS3,
+			&[
+				(hash(path).unwrap(), false, 1, 0x100),
+				(hash("exd/other.exl").unwrap(), false, 1, 0x180),
+			],
+			&[],
+		);
+		let index = Index1::read(&mut Cursor::new(bytes)).unwrap();
+		assert_eq!(index.platform(), Platform::PS3);
+
+		let (metadata, size) = index.find(path).unwrap();
+		assert_eq!(metadata.data_file_id, 1);
+		assert_eq!(metadata.offset, 0x100);
+		assert_eq!(size, Some(0x80));
 	}
 }
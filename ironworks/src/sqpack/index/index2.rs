@@ -1,5 +1,23 @@
-use binrw::BinRead;
+use binrw::binread;
+
+use crate::sqpack::Platform;
+
+use super::shared::SqPackHeader;
 
 // todo
-#[derive(BinRead, Debug)]
-pub struct Index2 {}
+#[binread]
+#[derive(Debug)]
+#[br(little)]
+pub struct Index2 {
+	#[br(temp)]
+	sqpack_header: SqPackHeader,
+
+	#[br(calc = sqpack_header.platform)]
+	platform: Platform,
+}
+
+impl Index2 {
+	pub fn platform(&self) -> Platform {
+		self.platform
+	}
+}
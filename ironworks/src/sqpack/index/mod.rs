@@ -1,7 +1,8 @@
 mod crc;
+mod hasher;
 mod index;
 mod index1;
 mod index2;
 mod shared;
 
-pub use index::{Index, Location};
+pub use index::{Index, IndexEntry, Location};
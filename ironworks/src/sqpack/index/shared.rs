@@ -2,25 +2,31 @@ use std::fmt;
 
 use binrw::BinRead;
 
+use crate::sqpack::Platform;
+
 #[derive(BinRead, Debug)]
-#[br(little, magic = b"SqPack\0\0")]
+#[br(magic = b"SqPack\0\0")]
 pub struct SqPackHeader {
-	_platform_id: u8,
+	pub platform: Platform,
 	// unknown: [u8; 3],
-	#[br(pad_before = 3)]
+	#[br(pad_before = 3, is_big = platform.is_big_endian())]
 	pub size: u32,
+	#[br(is_big = platform.is_big_endian())]
 	_version: u32,
+	#[br(is_big = platform.is_big_endian())]
 	_kind: u32,
 }
 
+// Endianness of the following structures is dependant on the platform of the
+// SqPack, and must be provided by the caller.
+
 #[derive(BinRead, Debug)]
-#[br(little)]
 pub struct IndexHeader {
 	_size: u32,
 	_version: u32,
 	pub index_data: Section,
 	_data_file_count: u32,
-	_synonym_data: Section,
+	pub synonym_data: Section,
 	_empty_block_data: Section,
 	_dir_index_data: Section,
 	_index_type: u32,
@@ -30,7 +36,6 @@ pub struct IndexHeader {
 }
 
 #[derive(BinRead, Debug)]
-#[br(little)]
 pub struct Section {
 	pub offset: u32,
 	pub size: u32,
@@ -50,7 +55,7 @@ impl fmt::Debug for Digest {
 #[derive(BinRead, Clone, Debug)]
 #[br(map = Self::read)]
 pub struct FileMetadata {
-	is_synonym: bool,
+	pub is_synonym: bool,
 	pub data_file_id: u8,
 	pub offset: u32,
 }
@@ -64,3 +69,11 @@ impl FileMetadata {
 		}
 	}
 }
+
+#[derive(Debug)]
+pub struct ChunkEntry {
+	pub hash: u64,
+	pub path: Option<String>,
+	pub metadata: FileMetadata,
+	pub size: Option<u32>,
+}
@@ -10,7 +10,7 @@ use crate::{
 	utility::{TakeSeekable, TakeSeekableExt},
 };
 
-use super::{Location, Resource};
+use super::{Location, Platform, Resource};
 
 const TRY_PATHS: &[&str] = &[
 	r"C:\SquareEnix\FINAL FANTASY XIV - A Realm Reborn",
@@ -24,13 +24,7 @@ const WSL_PREFIX: &[&str] = &["/mnt", "c"];
 
 const SQPACK_PATH: &[&str] = &["game", "sqpack"];
 
-#[allow(dead_code)]
-#[derive(Debug)]
-enum Platform {
-	Win32 = 0,
-	PS3 = 1,
-	PS4 = 2,
-}
+const PLATFORMS: &[Platform] = &[Platform::Win32, Platform::PS3, Platform::PS4];
 
 /// SqPack resource for reading game data from an on-disk FFXIV installation.
 #[derive(Debug)]
@@ -55,14 +49,22 @@ impl Install {
 			.collect::<PathBuf>();
 
 		let repositories = find_repositories(&sqpack_path);
+		let platform = find_platform(&sqpack_path, &repositories);
 
 		Self {
 			path: sqpack_path,
 			repositories,
-			platform: Platform::Win32,
+			platform,
 		}
 	}
 
+	/// Platform the installation's SqPack files were built for. This is detected
+	/// from the file names present in the installation, defaulting to
+	/// `Platform::Win32` if no SqPack files could be found.
+	pub fn platform(&self) -> Platform {
+		self.platform
+	}
+
 	fn build_file_path(
 		&self,
 		repository: u8,
@@ -70,12 +72,7 @@ impl Install {
 		chunk: u8,
 		extension: &str,
 	) -> Result<PathBuf> {
-		let platform = match self.platform {
-			Platform::Win32 => "win32",
-			Platform::PS3 => todo!("PS3 platform"),
-			Platform::PS4 => todo!("PS4 platform"),
-		};
-
+		let platform = self.platform.suffix();
 		let file_name = format!("{category:02x}{repository:02x}{chunk:02x}.{platform}.{extension}");
 
 		let file_path = self.path.join(
@@ -173,6 +170,30 @@ fn find_repositories(path: &Path) -> Vec<Option<String>> {
 		.collect()
 }
 
+fn find_platform(path: &Path, repositories: &[Option<String>]) -> Platform {
+	// Every platform ships index files for each repository, so checking the
+	// suffixes of the files in the first available repository is sufficient.
+	let entries = repositories
+		.iter()
+		.flatten()
+		.next()
+		.and_then(|repository| fs::read_dir(path.join(repository)).ok());
+
+	let Some(entries) = entries else {
+		return Platform::default();
+	};
+
+	entries
+		.filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+		.find_map(|file_name| {
+			PLATFORMS.iter().copied().find(|platform| {
+				file_name.ends_with(&format!(".{}.index", platform.suffix()))
+					|| file_name.ends_with(&format!(".{}.index2", platform.suffix()))
+			})
+		})
+		.unwrap_or_default()
+}
+
 fn read_index(path: PathBuf) -> Result<io::Cursor<Vec<u8>>> {
 	// Read the entire index into memory before returning - we typically need
 	// the full dataset anyway, and working directly on a File causes significant
@@ -185,3 +206,52 @@ fn read_index(path: PathBuf) -> Result<io::Cursor<Vec<u8>>> {
 	})?;
 	Ok(io::Cursor::new(buffer))
 }
+
+#[cfg(test)]
+mod test {
+	use std::{fs, path::PathBuf, process};
+
+	use crate::sqpack::Platform;
+
+	use super::Install;
+
+	// Create an empty installation layout containing the specified SqPack files
+	// within the base repository.
+	fn build_install(name: &str, files: &[&str]) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("ironworks-{name}-{}", process::id()));
+		let repository = path.join("game").join("sqpack").join("ffxiv");
+		fs::create_dir_all(&repository).unwrap();
+		for file in files {
+			fs::write(repository.join(file), []).unwrap();
+		}
+		path
+	}
+
+	#[test]
+	fn platform_win32() {
+		let path = build_install("win32", &["0a0000.win32.index", "0a0000.win32.dat0"]);
+		assert_eq!(Install::at(&path).platform(), Platform::Win32);
+		fs::remove_dir_all(path).unwrap();
+	}
+
+	#[test]
+	fn platform_ps3() {
+		let path = build_install("ps3", &["0a0000.ps3.dat0", "0a0000.ps3.index"]);
+		assert_eq!(Install::at(&path).platform(), Platform::PS3);
+		fs::remove_dir_all(path).unwrap();
+	}
+
+	#[test]
+	fn platform_ps4() {
+		let path = build_install("ps4", &["0a0000.ps4.index2"]);
+		assert_eq!(Install::at(&path).platform(), Platform::PS4);
+		fs::remove_dir_all(path).unwrap();
+	}
+
+	#[test]
+	fn platform_default() {
+		let path = build_install("empty", &[]);
+		assert_eq!(Install::at(&path).platform(), Platform::Win32);
+		fs::remove_dir_all(path).unwrap();
+	}
+}
@@ -4,14 +4,16 @@ mod block;
 mod file;
 mod index;
 mod install;
+mod platform;
 mod resource;
 mod sqpack;
 
 pub use {
 	block::{BlockMetadata, BlockPayload, BlockStream},
 	file::File,
-	index::Location,
+	index::{IndexEntry, Location},
 	install::Install,
+	platform::Platform,
 	resource::Resource,
 	sqpack::SqPack,
 };
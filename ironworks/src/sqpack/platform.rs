@@ -0,0 +1,36 @@
+use binrw::{BinRead, Endian};
+
+/// Platform a SqPack archive was built for.
+#[derive(BinRead, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[br(repr = u8)]
+pub enum Platform {
+	/// Windows.
+	#[default]
+	Win32 = 0,
+	/// PlayStation 3.
+	PS3 = 1,
+	/// PlayStation 4.
+	PS4 = 2,
+}
+
+impl Platform {
+	/// File name suffix used by SqPack files for this platform, i.e. `000000.win32.index`.
+	pub fn suffix(&self) -> &'static str {
+		match self {
+			Self::Win32 => "win32",
+			Self::PS3 => "ps3",
+			Self::PS4 => "ps4",
+		}
+	}
+
+	pub(crate) fn endian(&self) -> Endian {
+		match self {
+			Self::PS3 => Endian::Big,
+			Self::Win32 | Self::PS4 => Endian::Little,
+		}
+	}
+
+	pub(crate) fn is_big_endian(&self) -> bool {
+		self.endian() == Endian::Big
+	}
+}
@@ -8,7 +8,10 @@ use crate::{
 	Resource,
 };
 
-use super::{file::File, index::Index};
+use super::{
+	file::File,
+	index::{Index, IndexEntry},
+};
 
 const CATEGORIES: &[Option<&str>] = &[
 	/* 0x00 */ Some("common"),
@@ -65,24 +68,51 @@ impl<R: sqpack::Resource> SqPack<R> {
 
 	/// Read the file at `path` from SqPack.
 	pub fn file(&self, path: &str) -> Result<File<R::File>> {
+		// Look up the location of the requested path.
+		let entry = self.entry(path)?;
+
+		// TODO: Cache files? Tempted to say it's the IW struct's responsibility. Is it even possible here with streams?
+		self.entry_file(&entry)
+	}
+
+	/// Look up the index entry for the file at `path`.
+	pub fn entry(&self, path: &str) -> Result<IndexEntry> {
 		// SqPack paths are always lower case.
 		let path = path.to_lowercase();
-
-		// Look up the location of the requested path.
 		let (repository, category) = self.path_metadata(&path)?;
 
-		let location = self
-			.indexes
+		self.indexes
 			.try_get_or_insert((repository, category), || {
 				Index::new(repository, category, self.resource.clone())
 			})?
-			.find(&path)?;
+			.entry(&path)
+	}
 
-		// Build a File representation.
-		let dat = self.resource.file(repository, category, location)?;
+	/// List the files in the SqPack category containing `path`. Only the
+	/// category and repository segments of the path are considered, i.e.
+	/// `bg/ex1` will list the files in the `bg` category of the `ex1` repository.
+	///
+	/// SqPack indexes store hashes rather than full paths - paths will only be
+	/// available for entries with colliding hashes, see [`IndexEntry::is_collision`].
+	pub fn entries(&self, path: &str) -> Result<Vec<IndexEntry>> {
+		let (repository, category) = self.path_metadata(&path.to_lowercase())?;
 
-		// TODO: Cache files? Tempted to say it's the IW struct's responsibility. Is it even possible here with streams?
-		File::new(dat)
+		self.indexes
+			.try_get_or_insert((repository, category), || {
+				Index::new(repository, category, self.resource.clone())
+			})?
+			.entries()
+	}
+
+	/// Read the file listed by an index entry from SqPack.
+	pub fn entry_file(&self, entry: &IndexEntry) -> Result<File<R::File>> {
+		let location = entry.location().clone();
+		let platform = location.platform();
+		let dat = self
+			.resource
+			.file(entry.repository(), entry.category(), location)?;
+
+		File::with_platform(dat, platform)
 	}
 
 	fn path_metadata(&self, path: &str) -> Result<(u8, u8)> {
@@ -93,13 +123,13 @@ impl<R: sqpack::Resource> SqPack<R> {
 		let path_not_found = || Error::NotFound(ErrorValue::Path(path.to_string()));
 
 		let mut split = path.split('/');
-		let (Some(category_segment), Some(repository_segment)) = (split.next(), split.next()) else {
+		let (Some(category_segment), repository_segment) = (split.next(), split.next()) else {
 			return Err(path_not_found())
 		};
 
 		let repository = REPOSITORIES
 			.iter()
-			.position(|&repository| repository == repository_segment)
+			.position(|&repository| Some(repository) == repository_segment)
 			.unwrap_or(0);
 
 		let category = CATEGORIES
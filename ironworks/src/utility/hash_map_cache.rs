@@ -23,9 +23,16 @@ where
 		key: K,
 		build: impl FnOnce() -> Result<V, E>,
 	) -> Result<Arc<V>, E> {
+		if let Some(value) = self.lock().unwrap().get(&key) {
+			return Ok(value.clone());
+		}
+
+		// Build outside the lock, such that lookups of other keys are not blocked
+		// in the meantime. Should two builds of one key race, the first is kept.
+		let value = build()?;
 		Ok(match self.lock().unwrap().entry(key) {
 			Entry::Occupied(entry) => entry.get().clone(),
-			Entry::Vacant(entry) => entry.insert(build()?.into()).clone(),
+			Entry::Vacant(entry) => entry.insert(value.into()).clone(),
 		})
 	}
 }
@@ -0,0 +1,359 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use ironworks::excel::{Excel, Sheet};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	schema::{ReferenceTarget, Schema},
+};
+
+use super::{
+	metadata::{DynamicRow, SchemaSheet},
+	reference::{condition_matches, field_key, visit_references, ReferenceSource, ReverseIndex},
+	value::{Reference, Value},
+};
+
+// Row IDs keyed by the value of a selected column.
+type SelectorIndex = HashMap<i64, Vec<(u32, u16)>>;
+
+/// Adapter over an Excel database, reading sheets according to a schema.
+#[derive(Debug)]
+pub struct SchemaExcel<'i, S> {
+	excel: Excel<'i>,
+	schema: S,
+
+	selectors: Mutex<HashMap<(String, String), Arc<SelectorIndex>>>,
+}
+
+impl<'i, S: Schema> SchemaExcel<'i, S> {
+	/// Create an adapter reading sheets in `excel` with the provided schema.
+	/// Clones of an `Excel` share their caches, so a clone may be passed in
+	/// where the database is also used elsewhere.
+	pub fn new(excel: Excel<'i>, schema: S) -> Self {
+		Self {
+			excel,
+			schema,
+			selectors: Default::default(),
+		}
+	}
+
+	/// Underlying Excel database.
+	pub fn excel(&self) -> &Excel<'i> {
+		&self.excel
+	}
+
+	/// Schema used to read sheets.
+	pub fn schema(&self) -> &S {
+		&self.schema
+	}
+
+	/// Fetch a sheet, reading rows as dynamic values following its schema.
+	pub fn sheet(&self, name: &str) -> Result<Sheet<'i, SchemaSheet>> {
+		let schema = self.schema.sheet(name)?;
+		let columns = self.excel.sheet(name)?.columns()?;
+		let sheet = self.excel.sheet(SchemaSheet::new(schema, &columns))?;
+		Ok(sheet)
+	}
+
+	/// Resolve the rows targeted by a reference within `row`. Targets are checked
+	/// in the order declared by the schema, skipping those whose condition does
+	/// not hold for `row`, and the rows of the first target containing a matching
+	/// row are returned. Targets in sheets missing from the database or schema,
+	/// and references that do not point to an existing row, are ignored.
+	pub fn resolve(&self, row: &DynamicRow, reference: &Reference) -> Result<Vec<DynamicRow>> {
+		let Some(key) = field_key(&reference.value) else {
+			return Ok(vec![]);
+		};
+
+		for target in &reference.targets {
+			if !condition_matches(row, target) {
+				continue;
+			}
+
+			let rows = self.resolve_target(target, key)?;
+			if !rows.is_empty() {
+				return Ok(rows);
+			}
+		}
+
+		Ok(vec![])
+	}
+
+	/// Resolve the rows targeted by the reference at `path` within `row`. See
+	/// [`Value::get`] for the path format.
+	pub fn resolve_path(&self, row: &DynamicRow, path: &str) -> Result<Vec<DynamicRow>> {
+		match row.get(path) {
+			Some(Value::Reference(reference)) => self.resolve(row, reference),
+			_ => Err(Error::NotFound(ErrorValue::Other(format!(
+				"reference {path} in {} row {}",
+				row.sheet(),
+				row.row_id()
+			)))),
+		}
+	}
+
+	/// Build an index of the references made by every row in the specified
+	/// sheets. Sheets missing from the database or schema are skipped.
+	pub fn reverse_index<'a>(
+		&self,
+		sheets: impl IntoIterator<Item = &'a str>,
+	) -> Result<ReverseIndex> {
+		let mut index = ReverseIndex::default();
+
+		for name in sheets {
+			let Some(sheet) = self.find_sheet(name)? else {
+				continue;
+			};
+
+			for row in sheet.try_iter() {
+				let row = row?;
+				let mut references = vec![];
+				visit_references(row.value(), &mut String::new(), &mut |path, value| {
+					references.push((path.to_string(), value))
+				});
+
+				for (path, value) in references {
+					let Value::Reference(reference) = value else {
+						continue;
+					};
+
+					for target in self.resolve(&row, reference)? {
+						let source = ReferenceSource {
+							sheet: row.sheet().to_string(),
+							row_id: row.row_id(),
+							subrow_id: row.subrow_id(),
+							path: path.clone(),
+						};
+						index.insert(&target, source);
+					}
+				}
+			}
+		}
+
+		Ok(index)
+	}
+
+	/// Build an index of the references made by every sheet in the database.
+	pub fn reverse_index_all(&self) -> Result<ReverseIndex> {
+		let list = self.excel.list()?;
+		let names = list.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+		self.reverse_index(names.iter().map(String::as_str))
+	}
+
+	// Fetch a sheet, treating sheets missing from either the schema or the
+	// database as absent.
+	fn find_sheet(&self, name: &str) -> Result<Option<Sheet<'i, SchemaSheet>>> {
+		match self.sheet(name) {
+			Ok(sheet) => Ok(Some(sheet)),
+			Err(Error::NotFound(_)) => Ok(None),
+			Err(Error::Excel(error)) if matches!(*error, ironworks::Error::NotFound(_)) => Ok(None),
+			Err(error) => Err(error),
+		}
+	}
+
+	fn resolve_target(&self, target: &ReferenceTarget, key: i64) -> Result<Vec<DynamicRow>> {
+		let Some(sheet) = self.find_sheet(&target.sheet)? else {
+			return Ok(vec![]);
+		};
+
+		let ids = match &target.selector {
+			None => match u32::try_from(key) {
+				Ok(row_id) => vec![(row_id, 0)],
+				Err(_) => vec![],
+			},
+			Some(selector) => self
+				.selector_index(&sheet, selector)?
+				.get(&key)
+				.cloned()
+				.unwrap_or_default(),
+		};
+
+		let mut rows = vec![];
+		for (row_id, subrow_id) in ids {
+			match sheet.subrow(row_id, subrow_id) {
+				Ok(row) => rows.push(row),
+				Err(ironworks::Error::NotFound(_)) => {}
+				Err(error) => return Err(error.into()),
+			}
+		}
+
+		Ok(rows)
+	}
+
+	fn selector_index(
+		&self,
+		sheet: &Sheet<SchemaSheet>,
+		selector: &str,
+	) -> Result<Arc<SelectorIndex>> {
+		let key = (sheet.name(), selector.to_string());
+		if let Some(index) = self.selectors.lock().unwrap().get(&key) {
+			return Ok(index.clone());
+		}
+
+		let mut index = SelectorIndex::new();
+		for row in sheet.try_iter() {
+			let row = row?;
+			let Some(value) = row.get(selector).and_then(Value::field).and_then(field_key) else {
+				continue;
+			};
+			index
+				.entry(value)
+				.or_default()
+				.push((row.row_id(), row.subrow_id()));
+		}
+
+		let index = Arc::new(index);
+		self.selectors.lock().unwrap().insert(key, index.clone());
+
+		Ok(index)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{collections::HashMap, io::Cursor, sync::Arc};
+
+	use ironworks::{
+		excel::{Excel, Field, Language, Overlay, OverlayRow},
+		file::exh::{ColumnDefinition, ColumnKind, ExcelHeader, SheetKind},
+		FileStream, Ironworks, Resource,
+	};
+
+	use crate::{
+		error::{Error, ErrorValue, Result},
+		schema::{Node, Order, ReferenceTarget, Schema, Sheet, StructField},
+	};
+
+	use super::SchemaExcel;
+
+	// Resource serving an empty sheet list, with every sheet provided by overlays.
+	struct ListResource;
+
+	impl Resource for ListResource {
+		fn version(&self, _path: &str) -> Result<String, ironworks::Error> {
+			Ok("test".into())
+		}
+
+		fn file(&self, path: &str) -> Result<Box<dyn FileStream>, ironworks::Error> {
+			match path {
+				"exd/root.exl" => Ok(Box::new(Cursor::new(b"EXLT\r\n".to_vec()))),
+				_ => Err(ironworks::Error::NotFound(ironworks::ErrorValue::Path(
+					path.into(),
+				))),
+			}
+		}
+	}
+
+	struct TestSchema(HashMap<String, Node>);
+
+	impl Schema for TestSchema {
+		fn sheet(&self, name: &str) -> Result<Sheet> {
+			let node = self
+				.0
+				.get(name)
+				.ok_or_else(|| Error::NotFound(ErrorValue::Other(name.into())))?;
+			Ok(Sheet {
+				name: name.into(),
+				order: Order::Index,
+				node: node.clone(),
+			})
+		}
+	}
+
+	fn node(name: &str, node: Node) -> Node {
+		Node::Struct(vec![StructField {
+			offset: 0,
+			name: name.into(),
+			node,
+		}])
+	}
+
+	fn target(sheet: &str) -> ReferenceTarget {
+		ReferenceTarget {
+			sheet: sheet.into(),
+			selector: None,
+			condition: None,
+		}
+	}
+
+	// Sheets with a single u32 column. `Source` references `Item`, `Missing` - a
+	// sheet missing from the database - and `EventItem`, in that order.
+	// `Unmapped` is missing from the schema.
+	fn excel() -> SchemaExcel<'static, TestSchema> {
+		let sheets: &[(&str, &[(u32, u32)])] = &[
+			("Item", &[(1, 10)]),
+			("EventItem", &[(1, 20), (2, 30)]),
+			("Source", &[(0, 1), (1, 2), (2, 3)]),
+			("Unmapped", &[(0, 1)]),
+		];
+
+		let mut overlay = Overlay::new();
+		for &(sheet, rows) in sheets {
+			overlay
+				.sheet(
+					sheet,
+					ExcelHeader::new(
+						SheetKind::Default,
+						4,
+						vec![ColumnDefinition::new(ColumnKind::UInt32, 0)],
+						vec![],
+						0,
+						vec![Language::None.into()],
+					),
+				)
+				.rows(
+					sheet,
+					Language::None,
+					rows.iter()
+						.map(|&(row_id, value)| OverlayRow::new(row_id, vec![Field::U32(value)])),
+				);
+		}
+
+		let ironworks = Arc::new(Ironworks::new().with_resource(ListResource));
+		let excel = Excel::with().overlay(overlay).build(ironworks);
+
+		let reference =
+			Node::Reference(vec![target("Item"), target("Missing"), target("EventItem")]);
+		let schema = TestSchema(HashMap::from([
+			("Item".to_string(), node("Value", Node::Scalar)),
+			("EventItem".to_string(), node("Value", Node::Scalar)),
+			("Source".to_string(), node("Target", reference)),
+		]));
+
+		SchemaExcel::new(excel, schema)
+	}
+
+	fn resolve(excel: &SchemaExcel<TestSchema>, row_id: u32) -> Vec<(String, u32)> {
+		let row = excel.sheet("Source").unwrap().row(row_id).unwrap();
+		excel
+			.resolve_path(&row, "Target")
+			.unwrap()
+			.into_iter()
+			.map(|row| (row.sheet().to_string(), row.row_id()))
+			.collect()
+	}
+
+	#[test]
+	fn resolve_first_match() {
+		let excel = excel();
+		assert_eq!(resolve(&excel, 0), [("Item".to_string(), 1)]);
+		assert_eq!(resolve(&excel, 1), [("EventItem".to_string(), 2)]);
+		assert!(resolve(&excel, 2).is_empty());
+	}
+
+	#[test]
+	fn reverse_index() {
+		let index = excel().reverse_index_all().unwrap();
+		assert_eq!(index.references("Item", 1).len(), 1);
+		assert!(index.references("EventItem", 1).is_empty());
+
+		let sources = index.references("EventItem", 2);
+		assert_eq!(sources.len(), 1);
+		assert_eq!(sources[0].sheet, "Source");
+		assert_eq!(sources[0].row_id, 1);
+		assert_eq!(sources[0].path, "Target");
+	}
+}
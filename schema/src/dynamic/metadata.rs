@@ -0,0 +1,274 @@
+use ironworks::{
+	excel::{Field, Row, SheetMetadata},
+	file::exh::ColumnDefinition,
+};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	schema::{Node, Order, Sheet},
+};
+
+use super::value::{Reference, Value};
+
+/// Sheet metadata that reads rows as dynamic values following a sheet schema.
+#[derive(Debug, Clone)]
+pub struct SchemaSheet {
+	schema: Sheet,
+	// Indices of the sheet's columns, in the order expected by the schema.
+	columns: Vec<usize>,
+}
+
+impl SchemaSheet {
+	/// Create metadata for a sheet with the provided schema and column
+	/// definitions, as read from the sheet's header.
+	pub fn new(schema: Sheet, columns: &[ColumnDefinition]) -> Self {
+		let mut indices = (0..columns.len()).collect::<Vec<_>>();
+		if schema.order == Order::Offset {
+			indices.sort_by_key(|&index| {
+				let column = &columns[index];
+				(column.offset(), u16::from(column.kind()))
+			});
+		}
+
+		Self {
+			schema,
+			columns: indices,
+		}
+	}
+
+	/// Schema used to read rows.
+	pub fn schema(&self) -> &Sheet {
+		&self.schema
+	}
+
+	fn read(&self, row: &Row) -> Result<Value> {
+		read_node(&self.schema.node, 0, self.columns.len(), &mut |index| {
+			let column = self
+				.columns
+				.get(index)
+				.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("column {index}"))))?;
+			Ok(row.field(*column)?)
+		})
+	}
+}
+
+impl SheetMetadata for SchemaSheet {
+	fn name(&self) -> String {
+		self.schema.name.clone()
+	}
+
+	type Row = DynamicRow;
+	type Error = Error;
+	fn populate_row(&self, row: Row) -> Result<Self::Row> {
+		Ok(DynamicRow::new(
+			self.schema.name.clone(),
+			row.row_id(),
+			row.subrow_id(),
+			self.read(&row)?,
+		))
+	}
+}
+
+/// A (sub)row read according to a sheet schema.
+#[derive(Debug)]
+pub struct DynamicRow {
+	sheet: String,
+	row_id: u32,
+	subrow_id: u16,
+	value: Value,
+}
+
+impl DynamicRow {
+	pub(super) fn new(sheet: String, row_id: u32, subrow_id: u16, value: Value) -> Self {
+		Self {
+			sheet,
+			row_id,
+			subrow_id,
+			value,
+		}
+	}
+
+	/// Name of the sheet this row was read from.
+	pub fn sheet(&self) -> &str {
+		&self.sheet
+	}
+
+	/// ID of this row.
+	pub fn row_id(&self) -> u32 {
+		self.row_id
+	}
+
+	/// Subrow ID of this row.
+	pub fn subrow_id(&self) -> u16 {
+		self.subrow_id
+	}
+
+	/// Root value of the row.
+	pub fn value(&self) -> &Value {
+		&self.value
+	}
+
+	/// Consume the row, returning its root value.
+	pub fn into_value(self) -> Value {
+		self.value
+	}
+
+	/// Get the value at the specified path within the row. See [`Value::get`].
+	pub fn get(&self, path: &str) -> Option<&Value> {
+		self.value.get(path)
+	}
+}
+
+// Read a node starting at the column `offset`. `size` is the number of columns
+// available to the node, which may differ from the node's own size for the root.
+fn read_node(
+	node: &Node,
+	offset: usize,
+	size: usize,
+	read: &mut impl FnMut(usize) -> Result<Field>,
+) -> Result<Value> {
+	let value = match node {
+		Node::Scalar => Value::Scalar(read(offset)?),
+
+		Node::Reference(targets) => Value::Reference(Reference {
+			value: read(offset)?,
+			targets: targets.clone(),
+		}),
+
+		Node::Array { count, node } => {
+			let node_size = to_usize(node.size());
+			let values = (0..to_usize(*count))
+				.map(|index| read_node(node, offset + index * node_size, node_size, read))
+				.collect::<Result<Vec<_>>>()?;
+			Value::Array(values)
+		}
+
+		Node::Struct(fields) => {
+			// Walk the columns available to the struct, filling any gaps in the schema
+			// with unknown fields. Schema fields that extend past the available columns
+			// are omitted.
+			let mut values = vec![];
+			let mut field_offset = 0;
+			while field_offset < size {
+				let field = fields.iter().find(|field| {
+					to_usize(field.offset) == field_offset
+						&& field_offset + to_usize(field.node.size()) <= size
+				});
+
+				let (name, value, field_size) = match field {
+					Some(field) => {
+						let field_size = to_usize(field.node.size());
+						let value =
+							read_node(&field.node, offset + field_offset, field_size, read)?;
+						(field.name.clone(), value, field_size)
+					}
+					None => (
+						format!("unknown{field_offset}"),
+						Value::Scalar(read(offset + field_offset)?),
+						1,
+					),
+				};
+
+				values.push((name, value));
+				// Guard against zero-sized nodes, such as empty structs.
+				field_offset += field_size.max(1);
+			}
+
+			Value::Struct(values)
+		}
+	};
+
+	Ok(value)
+}
+
+fn to_usize(value: u32) -> usize {
+	usize::try_from(value).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::excel::Field;
+
+	use crate::{
+		error::{Error, ErrorValue},
+		schema::{Node, ReferenceTarget, StructField},
+	};
+
+	use super::{read_node, Value};
+
+	fn field(offset: u32, name: &str, node: Node) -> StructField {
+		StructField {
+			offset,
+			name: name.into(),
+			node,
+		}
+	}
+
+	fn read(node: &Node, columns: usize) -> Value {
+		read_node(node, 0, columns, &mut |index| match index < columns {
+			true => Ok(Field::U32(index.try_into().unwrap())),
+			false => Err(Error::NotFound(ErrorValue::Other(index.to_string()))),
+		})
+		.unwrap()
+	}
+
+	fn u32_at(value: &Value, path: &str) -> Option<u32> {
+		value.get(path)?.field()?.as_u32().copied()
+	}
+
+	#[test]
+	fn struct_fields() {
+		let node = Node::Struct(vec![
+			field(0, "Name", Node::Scalar),
+			field(
+				2,
+				"Item",
+				Node::Reference(vec![ReferenceTarget {
+					sheet: "Item".into(),
+					selector: None,
+					condition: None,
+				}]),
+			),
+		]);
+		let value = read(&node, 4);
+
+		assert_eq!(u32_at(&value, "Name"), Some(0));
+		assert_eq!(u32_at(&value, "unknown1"), Some(1));
+		assert_eq!(u32_at(&value, "Item"), Some(2));
+		assert_eq!(u32_at(&value, "unknown3"), Some(3));
+		assert!(matches!(value.get("Item"), Some(Value::Reference(_))));
+	}
+
+	#[test]
+	fn nested_arrays() {
+		let node = Node::Struct(vec![field(
+			1,
+			"Ingredient",
+			Node::Array {
+				count: 2,
+				node: Box::new(Node::Struct(vec![
+					field(0, "Item", Node::Scalar),
+					field(1, "Amount", Node::Scalar),
+				])),
+			},
+		)]);
+		let value = read(&node, 5);
+
+		assert_eq!(u32_at(&value, "Ingredient[0].Item"), Some(1));
+		assert_eq!(u32_at(&value, "Ingredient[0].Amount"), Some(2));
+		assert_eq!(u32_at(&value, "Ingredient[1].Item"), Some(3));
+		assert_eq!(u32_at(&value, "Ingredient[1].Amount"), Some(4));
+	}
+
+	#[test]
+	fn fields_past_columns() {
+		let node = Node::Struct(vec![
+			field(0, "Name", Node::Scalar),
+			field(1, "Missing", Node::Scalar),
+		]);
+		let value = read(&node, 1);
+
+		assert_eq!(u32_at(&value, "Name"), Some(0));
+		assert!(value.get("Missing").is_none());
+	}
+}
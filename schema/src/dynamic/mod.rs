@@ -0,0 +1,43 @@
+//! Dynamic, schema-driven access to Excel rows, for use where generated sheet
+//! types are not available.
+
+mod excel;
+mod metadata;
+mod reference;
+mod value;
+
+pub use {
+	excel::SchemaExcel,
+	metadata::{DynamicRow, SchemaSheet},
+	reference::{ReferenceSource, ReverseIndex},
+	value::{Reference, Value},
+};
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_send() {
+		fn assert_send<T: Send>() {}
+		assert_send::<DynamicRow>();
+		assert_send::<Reference>();
+		assert_send::<ReferenceSource>();
+		assert_send::<ReverseIndex>();
+		assert_send::<SchemaExcel<()>>();
+		assert_send::<SchemaSheet>();
+		assert_send::<Value>();
+	}
+
+	#[test]
+	fn test_sync() {
+		fn assert_sync<T: Sync>() {}
+		assert_sync::<DynamicRow>();
+		assert_sync::<Reference>();
+		assert_sync::<ReferenceSource>();
+		assert_sync::<ReverseIndex>();
+		assert_sync::<SchemaExcel<()>>();
+		assert_sync::<SchemaSheet>();
+		assert_sync::<Value>();
+	}
+}
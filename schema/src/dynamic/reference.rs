@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use ironworks::excel::Field;
+
+use crate::schema::ReferenceTarget;
+
+use super::{metadata::DynamicRow, value::Value};
+
+/// Index of references between rows, keyed by the referenced row.
+#[derive(Debug, Default)]
+pub struct ReverseIndex {
+	references: HashMap<(String, u32), Vec<ReferenceSource>>,
+}
+
+impl ReverseIndex {
+	/// Get the sources referencing the specified row.
+	pub fn references(&self, sheet: &str, row_id: u32) -> &[ReferenceSource] {
+		self.references
+			.get(&(sheet.to_string(), row_id))
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+
+	/// Iterate over all referenced rows, alongside their sources.
+	pub fn iter(&self) -> impl Iterator<Item = ((&str, u32), &[ReferenceSource])> {
+		self.references
+			.iter()
+			.map(|((sheet, row_id), sources)| ((sheet.as_str(), *row_id), sources.as_slice()))
+	}
+
+	pub(super) fn insert(&mut self, target: &DynamicRow, source: ReferenceSource) {
+		self.references
+			.entry((target.sheet().to_string(), target.row_id()))
+			.or_default()
+			.push(source);
+	}
+}
+
+/// Location of a reference to a row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceSource {
+	/// Sheet containing the reference.
+	pub sheet: String,
+	/// Row ID of the referencing row.
+	pub row_id: u32,
+	/// Subrow ID of the referencing row.
+	pub subrow_id: u16,
+	/// Path to the reference field within the referencing row, in the format
+	/// accepted by [`Value::get`].
+	pub path: String,
+}
+
+// Integer key used to compare reference values against conditions and selected
+// columns. Values that cannot be represented are treated as unmatchable.
+pub(super) fn field_key(field: &Field) -> Option<i64> {
+	let key = match field {
+		Field::Bool(value) => i64::from(*value),
+		Field::I8(value) => i64::from(*value),
+		Field::I16(value) => i64::from(*value),
+		Field::I32(value) => i64::from(*value),
+		Field::I64(value) => *value,
+		Field::U8(value) => i64::from(*value),
+		Field::U16(value) => i64::from(*value),
+		Field::U32(value) => i64::from(*value),
+		Field::U64(value) => i64::try_from(*value).ok()?,
+		Field::String(_) | Field::F32(_) => return None,
+	};
+	Some(key)
+}
+
+// Check if the target's condition, if any, holds for the source row.
+pub(super) fn condition_matches(row: &DynamicRow, target: &ReferenceTarget) -> bool {
+	let Some(condition) = &target.condition else {
+		return true;
+	};
+
+	row.get(&condition.selector)
+		.and_then(Value::field)
+		.and_then(field_key)
+		== Some(i64::from(condition.value))
+}
+
+// Walk a value, calling `visit` with the path of every reference within it.
+pub(super) fn visit_references<'v>(
+	value: &'v Value,
+	path: &mut String,
+	visit: &mut impl FnMut(&str, &'v Value),
+) {
+	match value {
+		Value::Reference(_) => visit(path, value),
+		Value::Scalar(_) => {}
+
+		Value::Array(values) => {
+			for (index, value) in values.iter().enumerate() {
+				let length = path.len();
+				path.push_str(&format!("[{index}]"));
+				visit_references(value, path, visit);
+				path.truncate(length);
+			}
+		}
+
+		Value::Struct(fields) => {
+			for (name, value) in fields {
+				let length = path.len();
+				if !path.is_empty() {
+					path.push('.');
+				}
+				path.push_str(name);
+				visit_references(value, path, visit);
+				path.truncate(length);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::excel::Field;
+
+	use crate::{
+		dynamic::{DynamicRow, Reference, Value},
+		schema::{ReferenceCondition, ReferenceTarget},
+	};
+
+	use super::{condition_matches, field_key, visit_references};
+
+	fn reference() -> Value {
+		Value::Reference(Reference {
+			value: Field::U32(1),
+			targets: vec![ReferenceTarget {
+				sheet: "Item".into(),
+				selector: None,
+				condition: Some(ReferenceCondition {
+					selector: "Type".into(),
+					value: 1,
+				}),
+			}],
+		})
+	}
+
+	#[test]
+	fn keys() {
+		assert_eq!(field_key(&Field::U8(5)), Some(5));
+		assert_eq!(field_key(&Field::I32(-1)), Some(-1));
+		assert_eq!(field_key(&Field::Bool(true)), Some(1));
+		assert_eq!(field_key(&Field::U64(u64::MAX)), None);
+		assert_eq!(field_key(&Field::F32(1.0)), None);
+	}
+
+	#[test]
+	fn conditions() {
+		let Value::Reference(reference) = reference() else {
+			unreachable!()
+		};
+		let target = &reference.targets[0];
+		let row = |kind| {
+			DynamicRow::new(
+				"Source".into(),
+				0,
+				0,
+				Value::Struct(vec![("Type".into(), Value::Scalar(Field::U8(kind)))]),
+			)
+		};
+
+		assert!(condition_matches(&row(1), target));
+		assert!(!condition_matches(&row(2), target));
+	}
+
+	#[test]
+	fn reference_paths() {
+		let value = Value::Struct(vec![
+			("Type".into(), Value::Scalar(Field::U8(1))),
+			("Item".into(), reference()),
+			(
+				"Ingredient".into(),
+				Value::Array(vec![
+					Value::Struct(vec![("Item".into(), reference())]),
+					Value::Struct(vec![("Item".into(), reference())]),
+				]),
+			),
+		]);
+
+		let mut paths = vec![];
+		visit_references(&value, &mut String::new(), &mut |path, _| {
+			paths.push(path.to_string())
+		});
+		assert_eq!(
+			paths,
+			vec!["Item", "Ingredient[0].Item", "Ingredient[1].Item"]
+		);
+	}
+}
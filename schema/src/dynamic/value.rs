@@ -0,0 +1,145 @@
+use ironworks::excel::Field;
+
+use crate::schema::ReferenceTarget;
+
+/// A value read from a row, following the shape of a schema node.
+#[derive(Debug)]
+pub enum Value {
+	/// Values of an array node, in order.
+	Array(Vec<Value>),
+
+	/// A field referencing rows in other sheets.
+	Reference(Reference),
+
+	/// A single field with no further semantics.
+	Scalar(Field),
+
+	/// Named values of a struct node, in column order. Columns not described by
+	/// the schema are named `unknown{offset}`.
+	Struct(Vec<(String, Value)>),
+}
+
+/// A field referencing rows in other sheets.
+#[derive(Debug)]
+pub struct Reference {
+	/// Value of the referencing field.
+	pub value: Field,
+	/// Potential targets of the reference, as declared by the schema.
+	pub targets: Vec<ReferenceTarget>,
+}
+
+impl Value {
+	/// Get the value at the specified path. Paths consist of struct field names
+	/// separated by `.`, with array indices in brackets, i.e. `Item.Name` or
+	/// `Ingredient[2].Amount`.
+	pub fn get(&self, path: &str) -> Option<&Value> {
+		parse_path(path)?
+			.into_iter()
+			.try_fold(self, |value, segment| value.child(segment))
+	}
+
+	/// Get the underlying field of scalar and reference values.
+	pub fn field(&self) -> Option<&Field> {
+		match self {
+			Self::Scalar(field) => Some(field),
+			Self::Reference(reference) => Some(&reference.value),
+			Self::Array(_) | Self::Struct(_) => None,
+		}
+	}
+
+	fn child(&self, segment: PathSegment) -> Option<&Value> {
+		match (self, segment) {
+			(Self::Struct(fields), PathSegment::Field(name)) => fields
+				.iter()
+				.find(|(field_name, _)| field_name == name)
+				.map(|(_, value)| value),
+			(Self::Array(values), PathSegment::Index(index)) => values.get(index),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq)]
+enum PathSegment<'a> {
+	Field(&'a str),
+	Index(usize),
+}
+
+fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+	let mut segments = vec![];
+
+	for part in path.split('.') {
+		let (name, mut indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+
+		match (name.is_empty(), indices.is_empty()) {
+			(true, true) => return None,
+			(false, _) => segments.push(PathSegment::Field(name)),
+			(true, false) => {}
+		}
+
+		while !indices.is_empty() {
+			let (index, rest) = indices.strip_prefix('[')?.split_once(']')?;
+			segments.push(PathSegment::Index(index.parse().ok()?));
+			indices = rest;
+		}
+	}
+
+	Some(segments)
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::excel::Field;
+
+	use super::{parse_path, PathSegment as S, Value};
+
+	#[test]
+	fn parse() {
+		assert_eq!(parse_path("Name"), Some(vec![S::Field("Name")]));
+		assert_eq!(
+			parse_path("Item.Name"),
+			Some(vec![S::Field("Item"), S::Field("Name")])
+		);
+		assert_eq!(
+			parse_path("Ingredient[2].Amount"),
+			Some(vec![
+				S::Field("Ingredient"),
+				S::Index(2),
+				S::Field("Amount")
+			])
+		);
+		assert_eq!(parse_path("[1][0]"), Some(vec![S::Index(1), S::Index(0)]));
+	}
+
+	#[test]
+	fn parse_invalid() {
+		assert_eq!(parse_path(""), None);
+		assert_eq!(parse_path("Item..Name"), None);
+		assert_eq!(parse_path("Item[a]"), None);
+		assert_eq!(parse_path("Item[1"), None);
+	}
+
+	#[test]
+	fn get() {
+		let value = Value::Struct(vec![
+			("Name".into(), Value::Scalar(Field::U32(1))),
+			(
+				"Ingredient".into(),
+				Value::Array(vec![
+					Value::Struct(vec![("Amount".into(), Value::Scalar(Field::U8(2)))]),
+					Value::Struct(vec![("Amount".into(), Value::Scalar(Field::U8(3)))]),
+				]),
+			),
+		]);
+
+		let field = |path| value.get(path).and_then(Value::field);
+		assert_eq!(field("Name").and_then(|f| f.as_u32()), Some(&1));
+		assert_eq!(
+			field("Ingredient[1].Amount").and_then(|f| f.as_u8()),
+			Some(&3)
+		);
+		assert!(value.get("Ingredient[2].Amount").is_none());
+		assert!(value.get("Missing").is_none());
+		assert!(value.get("Name[0]").is_none());
+	}
+}
@@ -1,4 +1,6 @@
 use core::fmt;
+#[cfg(feature = "excel")]
+use std::sync::Arc;
 
 /// An error that occured.
 #[derive(thiserror::Error, Debug, Clone)]
@@ -15,6 +17,12 @@ pub enum Error {
 	#[cfg(feature = "git2")]
 	#[error("{0}")]
 	Repository(String),
+
+	/// An error occured while reading Excel data. The original error is retained
+	/// such that callers can distinguish, i.e., missing and invalid data.
+	#[cfg(feature = "excel")]
+	#[error(transparent)]
+	Excel(Arc<ironworks::Error>),
 }
 
 #[cfg(feature = "git2")]
@@ -24,6 +32,13 @@ impl From<git2::Error> for Error {
 	}
 }
 
+#[cfg(feature = "excel")]
+impl From<ironworks::Error> for Error {
+	fn from(error: ironworks::Error) -> Self {
+		Error::Excel(Arc::new(error))
+	}
+}
+
 /// A value associated with an error.
 #[derive(Debug, Clone)]
 pub enum ErrorValue {
@@ -8,6 +8,8 @@
 mod error;
 mod schema;
 
+#[cfg(feature = "excel")]
+pub mod dynamic;
 #[cfg(feature = "saint_coinach")]
 pub mod saint_coinach;
 